@@ -0,0 +1,111 @@
+//! Throughput comparison of the crate's ciphers. Run with
+//! `cargo bench --bench cipher_comparison`.
+//!
+//! The block ciphers run in CBC mode and RC4 runs as a plain stream, each over
+//! the same 4 MB buffer in both directions. Every measurement is the best of a
+//! few runs, reported in MB/s. Numbers are only comparable on one machine.
+//! DES also runs block by block against its bitsliced batch path, without
+//! chaining, to show what the batch path gains.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use encrust::block::BlockCipher;
+use encrust::block::TripleDes;
+use encrust::block::aes::Aes;
+use encrust::block::des::Des;
+use encrust::modes::Cbc;
+use encrust::stream::arc4::AllegedRc4;
+
+/// Bytes processed per run.
+const BUFFER_LEN: usize = 4 * 1024 * 1024;
+/// Runs per measurement; the fastest one is reported.
+const RUNS: usize = 5;
+
+/// Times `f` over a fresh copy of the buffer and returns the fastest run.
+fn best_of(buffer: &[u8], mut f: impl FnMut(&mut [u8])) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let mut buf = buffer.to_vec();
+            let start = Instant::now();
+            f(black_box(&mut buf));
+            black_box(&buf);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, direction: &str, elapsed: Duration) {
+    let mb_per_s = BUFFER_LEN as f64 / 1e6 / elapsed.as_secs_f64();
+    println!("{name:<10} {direction:<8} {mb_per_s:>10.1} MB/s");
+}
+
+/// Measures CBC encryption and decryption under `cipher`.
+fn bench_cbc<C: BlockCipher>(name: &str, cipher: C, buffer: &[u8]) {
+    let iv = vec![0x24u8; C::BLOCK_SIZE];
+
+    let elapsed = best_of(buffer, |buf| {
+        Cbc::new(&cipher, &iv).unwrap().encrypt(buf).unwrap();
+    });
+    report(name, "encrypt", elapsed);
+
+    let elapsed = best_of(buffer, |buf| {
+        Cbc::new(&cipher, &iv).unwrap().decrypt(buf).unwrap();
+    });
+    report(name, "decrypt", elapsed);
+}
+
+/// Measures DES one block at a time against the bitsliced batch path.
+/// Both read the buffer as big-endian blocks, so the conversion costs the same.
+fn bench_des_batch(cipher: &Des, buffer: &[u8]) {
+    let to_blocks = |buf: &[u8]| -> Vec<u64> {
+        buf.chunks_exact(8)
+            .map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+            .collect()
+    };
+
+    let elapsed = best_of(buffer, |buf| {
+        let blocks: Vec<u64> = to_blocks(buf)
+            .into_iter()
+            .map(|b| cipher.encrypt(b))
+            .collect();
+        black_box(&blocks);
+    });
+    report("DES", "scalar", elapsed);
+
+    let elapsed = best_of(buffer, |buf| {
+        let mut blocks = to_blocks(buf);
+        cipher.encrypt_many(&mut blocks);
+        black_box(&blocks);
+    });
+    report("DES", "sliced", elapsed);
+}
+
+fn main() {
+    let buffer: Vec<u8> = (0..BUFFER_LEN).map(|i| (i * 31 + 7) as u8).collect();
+
+    bench_cbc("DES", Des::new(0x1334_5779_9BBC_DFF1), &buffer);
+    bench_des_batch(&Des::new(0x1334_5779_9BBC_DFF1), &buffer);
+    bench_cbc(
+        "3DES",
+        TripleDes::new(
+            0x0123_4567_89AB_CDEF,
+            0x2345_6789_ABCD_EF01,
+            0x4567_89AB_CDEF_0123,
+        ),
+        &buffer,
+    );
+    bench_cbc(
+        "AES-128",
+        Aes::new(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]).unwrap(),
+        &buffer,
+    );
+
+    // RC4 is its own inverse, so both directions are the same work.
+    let key = b"benchmark key";
+    for direction in ["encrypt", "decrypt"] {
+        let elapsed = best_of(&buffer, |buf| AllegedRc4::new(key).apply_keystream(buf));
+        report("RC4", direction, elapsed);
+    }
+}
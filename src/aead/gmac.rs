@@ -0,0 +1,190 @@
+use super::ghash::Ghash;
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::constant_time::ct_eq;
+
+/// Galois Message Authentication Code (GMAC, NIST SP 800-38D).
+///
+/// GCM with an empty plaintext: the additional data is hashed with GHASH and
+/// the hash is masked with the encryption of the pre-counter block `J0`.
+/// Needs a 128-bit block cipher, and a nonce must never be reused under one key.
+pub struct Gmac {
+    ghash: Ghash,
+    /// Encryption of `J0`, XORed into the hash to form the tag.
+    tag_mask: [u8; 16],
+    aad_len: u64,
+}
+
+impl Gmac {
+    /// Creates a `Gmac` from a cipher and a non-empty `nonce`.
+    /// 12-byte nonces are used as is, other lengths are hashed into `J0`.
+    ///
+    /// Returns [`Error::InvalidLength`] for an empty nonce or a cipher whose
+    /// block is not 128 bits, such as DES.
+    pub fn new<C: BlockCipher>(cipher: C, nonce: &[u8]) -> Result<Self, Error> {
+        if C::BLOCK_SIZE != 16 || nonce.is_empty() {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut h = [0u8; 16];
+        cipher.encrypt_block(&mut h);
+
+        let mut tag_mask = pre_counter_block(h, nonce);
+        cipher.encrypt_block(&mut tag_mask);
+
+        Ok(Self {
+            ghash: Ghash::new(h),
+            tag_mask,
+            aad_len: 0,
+        })
+    }
+
+    /// Feeds more additional data into the MAC.
+    pub fn update(&mut self, aad: &[u8]) {
+        self.ghash.update(aad);
+        self.aad_len += aad.len() as u64;
+    }
+
+    /// Returns the 16-byte tag.
+    pub fn finalize(mut self) -> [u8; 16] {
+        self.ghash.pad();
+        // Bit lengths of the additional data and of the (empty) ciphertext.
+        self.ghash.update(&(self.aad_len * 8).to_be_bytes());
+        self.ghash.update(&0u64.to_be_bytes());
+
+        let mut tag = self.ghash.finalize();
+        for (t, m) in tag.iter_mut().zip(self.tag_mask) {
+            *t ^= m;
+        }
+        tag
+    }
+
+    /// Computes the tag and compares it with `tag` in constant time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+}
+
+/// Derives `J0` from the nonce: `nonce || 0^31 || 1` for 12-byte nonces,
+/// otherwise the GHASH of the zero-padded nonce followed by its bit length.
+fn pre_counter_block(h: [u8; 16], nonce: &[u8]) -> [u8; 16] {
+    let mut j0 = [0u8; 16];
+    if nonce.len() == 12 {
+        j0[..12].copy_from_slice(nonce);
+        j0[15] = 1;
+        return j0;
+    }
+
+    let mut ghash = Ghash::new(h);
+    ghash.update(nonce);
+    ghash.pad();
+    ghash.update(&[0u8; 8]);
+    ghash.update(&(nonce.len() as u64 * 8).to_be_bytes());
+    ghash.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::aes::Aes;
+    use crate::block::des::Des;
+
+    const KEY: [u32; 4] = [0x77be6370, 0x8971c4e2, 0x40d1cb79, 0xe8d77feb];
+    const NONCE: [u8; 12] = [
+        0xe0, 0xe0, 0x0f, 0x19, 0xfe, 0xd7, 0xba, 0x01, 0x36, 0xa7, 0x97, 0xf3,
+    ];
+
+    fn gmac(key: [u32; 4], nonce: &[u8], aad: &[u8]) -> [u8; 16] {
+        let mut gmac = Gmac::new(Aes::new(&key).unwrap(), nonce).unwrap();
+        gmac.update(aad);
+        gmac.finalize()
+    }
+
+    /// NIST CAVP gcmEncryptExtIV128, PTlen = 0, AADlen = 128, count 0.
+    #[test]
+    fn test_nist_vector() {
+        let aad = [
+            0x7a, 0x43, 0xec, 0x1d, 0x9c, 0x0a, 0x5a, 0x78, 0xa0, 0xb1, 0x65, 0x33, 0xa6, 0x21,
+            0x3c, 0xab,
+        ];
+        let tag = [
+            0x20, 0x9f, 0xcc, 0x8d, 0x36, 0x75, 0xed, 0x93, 0x8e, 0x9c, 0x71, 0x66, 0x70, 0x9d,
+            0xd9, 0x46,
+        ];
+        assert_eq!(gmac(KEY, &NONCE, &aad), tag);
+    }
+
+    /// With no additional data GHASH is zero, so the tag is the encryption of `J0`.
+    #[test]
+    fn test_empty() {
+        let tag = [
+            0xbb, 0xbb, 0x74, 0x84, 0x69, 0xcf, 0xce, 0xb4, 0x91, 0x40, 0x79, 0xe2, 0x59, 0xdd,
+            0x9e, 0x9c,
+        ];
+        assert_eq!(gmac(KEY, &NONCE, b""), tag);
+    }
+
+    /// Reference tags from OpenSSL's AES-128-GCM with an empty plaintext.
+    #[test]
+    fn test_partial_block_and_long_nonce() {
+        let aad = b"header, then a longer trailing part of aad!";
+        let tag = [
+            0x34, 0x0a, 0xee, 0x0d, 0x3d, 0xed, 0x53, 0xad, 0x3f, 0xa2, 0x1a, 0x19, 0x14, 0xb5,
+            0x18, 0x2f,
+        ];
+        let mut split = Gmac::new(Aes::new(&KEY).unwrap(), &NONCE).unwrap();
+        for chunk in aad.chunks(5) {
+            split.update(chunk);
+        }
+        assert_eq!(split.finalize(), tag);
+
+        let nonce = [
+            0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88, 0xca, 0xfe,
+            0xba, 0xbe,
+        ];
+        let tag = [
+            0xbc, 0xd8, 0x31, 0x72, 0x38, 0xe0, 0x3e, 0xf9, 0x03, 0xda, 0xf6, 0x4f, 0x55, 0xd2,
+            0x46, 0x61,
+        ];
+        assert_eq!(gmac(KEY, &nonce, b"eight-byte nonce? no, sixteen"), tag);
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let tag = gmac(KEY, &NONCE, b"authentic header");
+
+        let mut gmac = Gmac::new(Aes::new(&KEY).unwrap(), &NONCE).unwrap();
+        gmac.update(b"authentic header");
+        assert_eq!(gmac.verify(&tag), Ok(()));
+
+        let mut gmac = Gmac::new(Aes::new(&KEY).unwrap(), &NONCE).unwrap();
+        gmac.update(b"authentic headeR");
+        assert_eq!(gmac.verify(&tag), Err(Error::AuthenticationFailed));
+
+        let mut forged = tag;
+        forged[0] ^= 1;
+        let mut gmac = Gmac::new(Aes::new(&KEY).unwrap(), &NONCE).unwrap();
+        gmac.update(b"authentic header");
+        assert_eq!(gmac.verify(&forged), Err(Error::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_empty_nonce() {
+        assert!(matches!(
+            Gmac::new(Aes::new(&KEY).unwrap(), &[]),
+            Err(Error::InvalidLength)
+        ));
+    }
+
+    #[test]
+    fn test_64_bit_block_cipher() {
+        assert!(matches!(
+            Gmac::new(Des::new(0x1334_5779_9BBC_DFF1), &NONCE),
+            Err(Error::InvalidLength)
+        ));
+    }
+}
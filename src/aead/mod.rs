@@ -0,0 +1,4 @@
+mod ghash;
+mod gmac;
+
+pub use gmac::Gmac;
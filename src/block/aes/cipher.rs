@@ -1,65 +1,307 @@
-// use super::s_box::S;
+use super::galois::mul;
+#[cfg(any(feature = "aes-small", test))]
+use super::galois::mul_inverse;
+#[cfg(any(not(feature = "aes-small"), test))]
+use super::s_box::INV_S;
+use super::s_box::S;
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::modes::Ctr;
+use alloc::vec::Vec;
 
+/// Round constants for the key expansion: powers of x in GF(2^8).
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Advanced Encryption Standard
 #[derive(Debug)]
 pub struct Aes {
-    // rounds: u8,
-    // round_keys: [u128; 15],
+    rounds: u8,
+    round_keys: [u128; 15],
 }
 
 impl Aes {
-    /// Creates an `Aes` instance from a `key` split into 32-bit chunks.
-    /// The effective key size must be 128, 192 or 256 bits.
-    pub fn new(key: [u32; 8]) -> Result<Self, &'static str> {
-        // Get the index of where the effective key starts.
-        let head = match key.iter().position(|&v| v != 0) {
-            Some(id) => id,
-            None => return Err("key cannot be empty"),
-        };
-
-        if !matches!(head, 0 | 2 | 4) {
-            return Err("key size must be 128, 192 or 256 bits");
-        };
-
-        let keys_len = 8 - head; // number of 32-bit chunks in 128/192/256-bit key
-        let rounds = 14 - head; // number of rounds
+    /// Creates an `Aes` instance from a `key` split into big-endian 32-bit words.
+    /// The number of words alone picks the key size: 4, 6 or 8 words select
+    /// AES-128, AES-192 or AES-256, whatever their values.
+    /// Other lengths give [`Error::InvalidKeyLength`].
+    pub fn new(key: &[u32]) -> Result<Self, Error> {
+        if !matches!(key.len(), 4 | 6 | 8) {
+            return Err(Error::InvalidKeyLength);
+        }
+        Ok(Self::from_key_words(key))
+    }
+
+    /// Creates an `Aes` instance from a 16, 24 or 32-byte `key`, which selects
+    /// AES-128, AES-192 or AES-256. Other lengths give [`Error::InvalidKeyLength`].
+    pub fn from_bytes(key: &[u8]) -> Result<Self, Error> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(Error::InvalidKeyLength);
+        }
+
+        let words: Vec<u32> = key
+            .chunks_exact(4)
+            .map(|w| u32::from_be_bytes(w.try_into().unwrap()))
+            .collect();
+        Ok(Self::from_key_words(&words))
+    }
+
+    /// Runs the key expansion on a key of 4, 6 or 8 32-bit words.
+    pub(crate) fn from_key_words(key: &[u32]) -> Self {
+        assert!(
+            matches!(key.len(), 4 | 6 | 8),
+            "key size must be 128, 192 or 256 bits"
+        );
+
+        let keys_len = key.len(); // number of 32-bit chunks in 128/192/256-bit key
+        let rounds = keys_len + 6; // number of rounds
         let words_in_key = 4; // amount of 32-bit words in a 128-bit block
         let words_len = words_in_key * (rounds + 1); // number of words in expanded 128-bit keys
 
-        // Up to 16 leading words can be 0s if the `head` != 0.
         let mut words: [u32; 4 * 15] = [0u32; 4 * 15];
-        let words_head = words.len() - words_len;
-
-        // TODO: how to test this indexing stuff:
-        // result should be the same when using arrays (and key) of smaller size
-        // meanwhile trust it works and focus on implementing smaller functions
-        for i in words_head..words.len() {
-            // To mitigate leading zeros we use a second index
-            let j = i - words_head; // j always begins with zero
 
-            if j < 8 {
-                words[i] = key[j]; // seed with original key
+        for j in 0..words_len {
+            if j < keys_len {
+                words[j] = key[j]; // seed with original key
             } else {
                 // expand words
-                let temp = words[i - 1];
+                let mut temp = words[j - 1];
 
                 if j.is_multiple_of(keys_len) {
-                    // 1. Rotate left auth Auth
-                    // 2. Apply s_box
-                    // 3. Expand with the rcon table: Rcon[i / keys_len]
+                    temp = sub_word(temp.rotate_left(8)) ^ ((RCON[j / keys_len - 1] as u32) << 24);
                 } else if (keys_len > 6) && (j % keys_len == 4) {
-                    // apply s_box
+                    temp = sub_word(temp);
                 }
 
-                words[i] = temp ^ words[i - keys_len];
+                words[j] = temp ^ words[j - keys_len];
             }
         }
 
-        // combine words into 128 bit round keys
+        // Combine words into 128-bit round keys.
+        let round_keys = core::array::from_fn(|r| {
+            words[4 * r..4 * r + 4]
+                .iter()
+                .fold(0u128, |acc, &w| (acc << 32) | w as u128)
+        });
 
-        Ok(Self {
-            // rounds: 14 - head as u8,
-            // round_keys: [0u128; 15],
-        })
+        Self {
+            rounds: rounds as u8,
+            round_keys,
+        }
+    }
+
+    /// Encrypt a 128-bit block of text.
+    pub fn encrypt(&self, plain_block: [u8; 16]) -> [u8; 16] {
+        self.encrypt_rounds(plain_block, self.rounds)
+    }
+
+    /// Encrypts with only the first `rounds` rounds, the last of them skipping
+    /// MixColumns like a real final round. Meant for studying reduced-round AES.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rounds` is zero or more than the key size allows.
+    pub fn encrypt_rounds(&self, block: [u8; 16], rounds: u8) -> [u8; 16] {
+        assert!(
+            (1..=self.rounds).contains(&rounds),
+            "rounds must be between 1 and {}",
+            self.rounds
+        );
+        let rounds = rounds as usize;
+        let mut state = block;
+
+        add_round_key(&mut state, self.round_keys[0]);
+        for round in 1..rounds {
+            sub_bytes(&mut state);
+            shift_rows(&mut state);
+            mix_columns(&mut state);
+            add_round_key(&mut state, self.round_keys[round]);
+        }
+
+        // The final round skips MixColumns.
+        sub_bytes(&mut state);
+        shift_rows(&mut state);
+        add_round_key(&mut state, self.round_keys[rounds]);
+
+        state
+    }
+
+    /// Decrypt a 128-bit block of text.
+    pub fn decrypt(&self, cipher_block: [u8; 16]) -> [u8; 16] {
+        #[cfg(feature = "aes-small")]
+        return self.decrypt_small(cipher_block);
+
+        #[cfg(not(feature = "aes-small"))]
+        return self.decrypt_table(cipher_block);
+    }
+
+    /// Straightforward inverse cipher using the inverse S-box table.
+    #[cfg(any(not(feature = "aes-small"), test))]
+    fn decrypt_table(&self, cipher_block: [u8; 16]) -> [u8; 16] {
+        let rounds = self.rounds as usize;
+        let mut state = cipher_block;
+
+        add_round_key(&mut state, self.round_keys[rounds]);
+        for round in (1..rounds).rev() {
+            inv_shift_rows(&mut state);
+            inv_sub_bytes(&mut state);
+            add_round_key(&mut state, self.round_keys[round]);
+            inv_mix_columns(&mut state);
+        }
+
+        inv_shift_rows(&mut state);
+        inv_sub_bytes(&mut state);
+        add_round_key(&mut state, self.round_keys[0]);
+
+        state
+    }
+
+    /// Equivalent inverse cipher (FIPS-197 5.3.5) for the `aes-small` feature.
+    ///
+    /// The round steps run in the same order as in encryption, so the inverse
+    /// S-box table is dropped and each byte is inverted arithmetically instead,
+    /// without secret-dependent branches or lookups. The InvMixColumns'd
+    /// decryption round keys are derived per block rather than stored. This
+    /// trades decryption speed, which is many times slower, for 256 bytes less
+    /// table data and no extra key storage.
+    #[cfg(any(feature = "aes-small", test))]
+    fn decrypt_small(&self, cipher_block: [u8; 16]) -> [u8; 16] {
+        let rounds = self.rounds as usize;
+        let mut state = cipher_block;
+
+        add_round_key(&mut state, self.round_keys[rounds]);
+        for round in (1..rounds).rev() {
+            inv_sub_bytes_small(&mut state);
+            inv_shift_rows(&mut state);
+            inv_mix_columns(&mut state);
+            add_round_key(&mut state, inv_mix_round_key(self.round_keys[round]));
+        }
+
+        inv_sub_bytes_small(&mut state);
+        inv_shift_rows(&mut state);
+        add_round_key(&mut state, self.round_keys[0]);
+
+        state
+    }
+
+    /// Creates a GCM-style counter mode: a fixed 96-bit nonce followed by a 32-bit
+    /// big-endian counter. Only the counter increments, wrapping around at 2^32.
+    pub fn ctr_gcm_style(self, nonce_96: [u8; 12], start_counter: u32) -> Ctr<Aes> {
+        let nonce = nonce_96
+            .iter()
+            .fold(0u128, |acc, &b| (acc << 8) | b as u128);
+
+        Ctr::with_nonce_counter(self, 96, nonce, start_counter as u128)
+            .expect("a 96-bit nonce and a 32-bit counter fill the block")
+    }
+
+    /// Pads `data` with PKCS#7 and encrypts every 16-byte block independently
+    /// (ECB mode) on the rayon thread pool.
+    ///
+    /// ECB leaks patterns in the data, as identical plaintext blocks give
+    /// identical ciphertext blocks. Prefer CBC or CTR from [`crate::modes`].
+    #[cfg(feature = "rayon")]
+    pub fn encrypt_ecb_par(&self, data: &[u8]) -> Vec<u8> {
+        crate::block::encrypt_ecb_par(self, data)
+    }
+}
+
+impl BlockCipher for Aes {
+    const BLOCK_SIZE: usize = 16;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let b: [u8; 16] = block.try_into().expect("AES block must be 16 bytes");
+        block.copy_from_slice(&self.encrypt(b));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let b: [u8; 16] = block.try_into().expect("AES block must be 16 bytes");
+        block.copy_from_slice(&self.decrypt(b));
+    }
+}
+
+/// Applies the S-box to each byte of a 32-bit word.
+fn sub_word(word: u32) -> u32 {
+    u32::from_be_bytes(word.to_be_bytes().map(|b| S[b as usize]))
+}
+
+/// XORs the state with a round key.
+fn add_round_key(state: &mut [u8; 16], round_key: u128) {
+    for (s, k) in state.iter_mut().zip(round_key.to_be_bytes()) {
+        *s ^= k;
+    }
+}
+
+fn sub_bytes(state: &mut [u8; 16]) {
+    for b in state.iter_mut() {
+        *b = S[*b as usize];
+    }
+}
+
+#[cfg(any(not(feature = "aes-small"), test))]
+fn inv_sub_bytes(state: &mut [u8; 16]) {
+    for b in state.iter_mut() {
+        *b = INV_S[*b as usize];
+    }
+}
+
+/// Inverts the S-box on each byte without a table: undoes the affine
+/// transformation, then takes the multiplicative inverse in GF(2^8).
+/// Neither step branches on or indexes memory with the state.
+#[cfg(any(feature = "aes-small", test))]
+fn inv_sub_bytes_small(state: &mut [u8; 16]) {
+    for b in state.iter_mut() {
+        *b = mul_inverse(b.rotate_left(1) ^ b.rotate_left(3) ^ b.rotate_left(6) ^ 0x05);
+    }
+}
+
+/// Applies InvMixColumns to a round key, turning it into a key for the
+/// equivalent inverse cipher.
+#[cfg(any(feature = "aes-small", test))]
+fn inv_mix_round_key(round_key: u128) -> u128 {
+    let mut key = round_key.to_be_bytes();
+    inv_mix_columns(&mut key);
+    u128::from_be_bytes(key)
+}
+
+/// Rotates row `r` of the column-major state left by `r` positions.
+fn shift_rows(state: &mut [u8; 16]) {
+    let old = *state;
+    for col in 0..4 {
+        for row in 0..4 {
+            state[4 * col + row] = old[4 * ((col + row) % 4) + row];
+        }
+    }
+}
+
+/// Rotates row `r` of the column-major state right by `r` positions.
+fn inv_shift_rows(state: &mut [u8; 16]) {
+    let old = *state;
+    for col in 0..4 {
+        for row in 0..4 {
+            state[4 * ((col + row) % 4) + row] = old[4 * col + row];
+        }
+    }
+}
+
+/// Multiplies each column by the polynomial {03}x^3 + {01}x^2 + {01}x + {02}.
+fn mix_columns(state: &mut [u8; 16]) {
+    for col in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [col[0], col[1], col[2], col[3]];
+        col[0] = mul(a0, 2) ^ mul(a1, 3) ^ a2 ^ a3;
+        col[1] = a0 ^ mul(a1, 2) ^ mul(a2, 3) ^ a3;
+        col[2] = a0 ^ a1 ^ mul(a2, 2) ^ mul(a3, 3);
+        col[3] = mul(a0, 3) ^ a1 ^ a2 ^ mul(a3, 2);
+    }
+}
+
+/// Multiplies each column by the polynomial {0b}x^3 + {0d}x^2 + {09}x + {0e}.
+fn inv_mix_columns(state: &mut [u8; 16]) {
+    for col in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [col[0], col[1], col[2], col[3]];
+        col[0] = mul(a0, 14) ^ mul(a1, 11) ^ mul(a2, 13) ^ mul(a3, 9);
+        col[1] = mul(a0, 9) ^ mul(a1, 14) ^ mul(a2, 11) ^ mul(a3, 13);
+        col[2] = mul(a0, 13) ^ mul(a1, 9) ^ mul(a2, 14) ^ mul(a3, 11);
+        col[3] = mul(a0, 11) ^ mul(a1, 13) ^ mul(a2, 9) ^ mul(a3, 14);
     }
 }
 
@@ -67,26 +309,291 @@ impl Aes {
 mod tests {
     use super::*;
 
+    const PLAINTEXT: [u8; 16] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, //
+        0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+    ];
+
     #[test]
     fn test_ksa() {
         #[rustfmt::skip]
         let key: [u32; 8] = [
-            2430607645, 2477337209, 3966267802, 2832764579, 
+            2430607645, 2477337209, 3966267802, 2832764579,
             4025463770, 2937464051, 2278884081, 3015632120,
         ];
 
-        Aes::new(key).expect("Aes::new failed for this key");
+        Aes::new(&key).expect("Aes::new failed for this key");
     }
 
     #[test]
-    // #[should_panic(expected = "key size must be 128, 192 or 256 bits")]
-    fn test_ksa_panic() {
+    fn test_ksa_invalid_length() {
         #[rustfmt::skip]
         let key: [u32; 8] = [
-                     0, 2477337209, 3966267802, 2832764579, 
+            2430607645, 2477337209, 3966267802, 2832764579,
             4025463770, 2937464051, 2278884081, 3015632120,
         ];
 
-        Aes::new(key).expect_err("key size must be 128, 192 or 256 bits");
+        for len in [0, 3, 5, 7] {
+            assert!(matches!(
+                Aes::new(&key[..len]),
+                Err(Error::InvalidKeyLength)
+            ));
+        }
+    }
+
+    /// Regression test: the key size used to be guessed from the first non-zero
+    /// word, which turned this 256-bit key into a 224-bit one and rejected it.
+    #[test]
+    fn test_leading_zero_word() {
+        let key = [
+            0, 0x04050607, 0x08090a0b, 0x0c0d0e0f, 0x10111213, 0x14151617, 0x18191a1b, 0x1c1d1e1f,
+        ];
+        let cipher = Aes::new(&key).unwrap();
+        assert_eq!(cipher.rounds, 14);
+        assert_eq!(
+            cipher.encrypt(PLAINTEXT),
+            [
+                0x44, 0x36, 0x32, 0x76, 0x7b, 0x5c, 0x00, 0xf5, //
+                0xa8, 0x54, 0x9b, 0x9e, 0x3e, 0x18, 0x92, 0x69,
+            ]
+        );
+    }
+
+    /// An all-zero key is a valid (if terrible) key, not an empty one.
+    #[test]
+    fn test_all_zero_key() {
+        let zero = Aes::new(&[0u32; 4]).unwrap();
+        assert_eq!(zero.rounds, 10);
+        assert_eq!(zero.round_keys[0], 0);
+        assert_eq!(zero.round_keys[1], 0x62636363_62636363_62636363_62636363);
+        assert_eq!(zero.round_keys[10], 0xb4ef5bcb_3e92e211_23e951cf_6f8f188e);
+
+        let ciphertext = [
+            0x66, 0xe9, 0x4b, 0xd4, 0xef, 0x8a, 0x2c, 0x3b, //
+            0x88, 0x4c, 0xfa, 0x59, 0xca, 0x34, 0x2b, 0x2e,
+        ];
+        assert_eq!(zero.encrypt([0u8; 16]), ciphertext);
+        assert_eq!(zero.decrypt(ciphertext), [0u8; 16]);
+
+        assert_eq!(
+            Aes::from_bytes(&[0u8; 16]).unwrap().encrypt([0u8; 16]),
+            ciphertext
+        );
+    }
+
+    /// FIPS-197 Appendix A key expansion examples: the key itself, the first
+    /// expanded round key and the last round key for each key size.
+    #[test]
+    fn test_key_expansion_fips_197() {
+        #[rustfmt::skip]
+        let cases: [(&[u32], u8, u128, u128); 3] = [
+            (
+                &[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c],
+                10,
+                0xa0fafe17_88542cb1_23a33939_2a6c7605,
+                0xd014f9a8_c9ee2589_e13f0cc8_b6630ca6,
+            ),
+            (
+                &[0x8e73b0f7, 0xda0e6452, 0xc810f32b, 0x809079e5, 0x62f8ead2, 0x522c6b7b],
+                12,
+                0x62f8ead2_522c6b7b_fe0c91f7_2402f5a5,
+                0xe98ba06f_448c773c_8ecc7204_01002202,
+            ),
+            (
+                &[0x603deb10, 0x15ca71be, 0x2b73aefd, 0x857d7781, 0x1f352c07, 0x3b6108d7, 0x2d9810a3, 0x0914dff4],
+                14,
+                0x1f352c07_3b6108d7_2d9810a3_0914dff4,
+                0xa5fa0366_e6aa8dbc_f6b77e4c_70b66316,
+            ),
+        ];
+
+        for (key, rounds, second, last) in cases {
+            let aes = Aes::from_key_words(key);
+            let seed = key[..4]
+                .iter()
+                .fold(0u128, |acc, &w| (acc << 32) | w as u128);
+
+            assert_eq!(aes.rounds, rounds);
+            assert_eq!(aes.round_keys[0], seed);
+            assert_eq!(aes.round_keys[1], second);
+            assert_eq!(aes.round_keys[rounds as usize], last);
+        }
+    }
+
+    #[test]
+    fn test_inv_sub_bytes_small() {
+        for chunk in 0..16u8 {
+            let mut state: [u8; 16] = core::array::from_fn(|i| 16 * chunk + i as u8);
+            let expected = state.map(|b| INV_S[b as usize]);
+            inv_sub_bytes_small(&mut state);
+            assert_eq!(state, expected);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_small_matches_table() {
+        let aes = Aes::from_key_words(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]);
+
+        let mut block = PLAINTEXT;
+        for _ in 0..64 {
+            block = aes.encrypt(block);
+            assert_eq!(aes.decrypt_small(block), aes.decrypt_table(block));
+        }
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let key: Vec<u8> = (0..32).collect();
+        for (len, rounds) in [(16, 10), (24, 12), (32, 14)] {
+            let aes = Aes::from_bytes(&key[..len]).unwrap();
+            assert_eq!(aes.rounds, rounds);
+        }
+
+        // FIPS-197 Appendix C.1.
+        let aes = Aes::from_bytes(&key[..16]).unwrap();
+        assert_eq!(
+            aes.encrypt(PLAINTEXT),
+            [
+                0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, //
+                0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a,
+            ]
+        );
+
+        for len in [0, 15, 20, 33] {
+            assert!(matches!(
+                Aes::from_bytes(&vec![1u8; len]),
+                Err(Error::InvalidKeyLength)
+            ));
+        }
+    }
+
+    /// FIPS-197 Appendix C example vectors.
+    #[test]
+    fn test_encrypt_decrypt_fips_197() {
+        #[rustfmt::skip]
+        let cases: [(&[u32], [u8; 16]); 3] = [
+            (
+                &[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f],
+                [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a],
+            ),
+            (
+                &[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f, 0x10111213, 0x14151617],
+                [0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91],
+            ),
+            (
+                &[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f, 0x10111213, 0x14151617, 0x18191a1b, 0x1c1d1e1f],
+                [0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89],
+            ),
+        ];
+
+        for (key, expected) in cases {
+            let cipher = Aes::new(key).unwrap();
+            assert_eq!(cipher.encrypt(PLAINTEXT), expected);
+            assert_eq!(cipher.decrypt(expected), PLAINTEXT);
+        }
+    }
+
+    /// Carry-less product reduced modulo 0x11B, independent of `mul`.
+    fn reference_mul(a: u8, b: u8) -> u8 {
+        let mut product = 0u16;
+        for bit in 0..8 {
+            if (b >> bit) & 1 == 1 {
+                product ^= (a as u16) << bit;
+            }
+        }
+        for bit in (8..15).rev() {
+            if (product >> bit) & 1 == 1 {
+                product ^= 0x11B << (bit - 8);
+            }
+        }
+        product as u8
+    }
+
+    /// Multiplies every column of the state by a 4x4 matrix over GF(2^8).
+    fn matrix_mul(matrix: [[u8; 4]; 4], state: [u8; 16]) -> [u8; 16] {
+        core::array::from_fn(|i| {
+            let (col, row) = (i / 4, i % 4);
+            (0..4).fold(0, |acc, k| {
+                acc ^ reference_mul(matrix[row][k], state[4 * col + k])
+            })
+        })
+    }
+
+    #[test]
+    fn test_mix_columns_matrix() {
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let mix = [[2, 3, 1, 1], [1, 2, 3, 1], [1, 1, 2, 3], [3, 1, 1, 2]];
+        let inv_mix = [
+            [14, 11, 13, 9],
+            [9, 14, 11, 13],
+            [13, 9, 14, 11],
+            [11, 13, 9, 14],
+        ];
+        let mut rng = StdRng::seed_from_u64(197);
+
+        for _ in 0..1000 {
+            let state: [u8; 16] = rng.r#gen();
+
+            let mut mixed = state;
+            mix_columns(&mut mixed);
+            assert_eq!(mixed, matrix_mul(mix, state));
+
+            let mut unmixed = state;
+            inv_mix_columns(&mut unmixed);
+            assert_eq!(unmixed, matrix_mul(inv_mix, state));
+
+            inv_mix_columns(&mut mixed);
+            assert_eq!(mixed, state);
+        }
+    }
+
+    #[test]
+    fn test_ctr_gcm_style() {
+        let key = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+        let nonce: [u8; 12] = core::array::from_fn(|i| i as u8 + 1);
+        let nonce_value = u128::from_be_bytes([&nonce[..], &[0u8; 4]].concat().try_into().unwrap());
+
+        let mut ctr = Aes::new(&key).unwrap().ctr_gcm_style(nonce, u32::MAX - 1);
+        let mut buf = [0u8; 32];
+        ctr.apply_keystream(&mut buf);
+
+        // The counter sits in the low 32 bits of the first keystream block.
+        let counter_block = nonce_value | (u32::MAX - 1) as u128;
+        assert_eq!(
+            buf[..16],
+            Aes::new(&key).unwrap().encrypt(counter_block.to_be_bytes())
+        );
+
+        // The counter wraps at 2^32 without touching the nonce.
+        assert_eq!(ctr.counter(), nonce_value);
+        ctr.apply_keystream(&mut buf[..16]);
+        assert_eq!(ctr.counter(), nonce_value | 1);
+    }
+
+    #[test]
+    fn test_encrypt_rounds() {
+        let key = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+        let cipher = Aes::new(&key).unwrap();
+        let block: [u8; 16] = core::array::from_fn(|i| (i * 0x11) as u8);
+
+        assert_eq!(cipher.encrypt_rounds(block, 10), cipher.encrypt(block));
+
+        // Flipping one input byte spreads to 1, then 4, then all 16 output bytes.
+        let mut flipped = block;
+        flipped[0] ^= 1;
+        for (rounds, spread) in [(1, 1), (2, 4), (3, 16)] {
+            let a = cipher.encrypt_rounds(block, rounds);
+            let b = cipher.encrypt_rounds(flipped, rounds);
+            let changed = a.iter().zip(b).filter(|&(x, y)| *x != y).count();
+            assert_eq!(changed, spread, "{} rounds", rounds);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "rounds must be between 1 and 10")]
+    fn test_encrypt_rounds_panic() {
+        let key = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+        Aes::new(&key).unwrap().encrypt_rounds([0u8; 16], 11);
     }
 }
@@ -0,0 +1,103 @@
+//! Arithmetic in GF(2^8) modulo the AES polynomial x^8 + x^4 + x^3 + x + 1 (0x11B).
+
+/// Multiplies `a` by x, reducing by the AES polynomial when the top bit falls off.
+pub(super) fn xtime(a: u8) -> u8 {
+    let carry = a >> 7;
+    (a << 1) ^ (carry * 0x1b)
+}
+
+/// Multiplies two field elements using the Russian-peasant method.
+pub(super) fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplies two field elements in a fixed eight steps, masking instead of
+/// branching on the bits of `b`, for when both operands are secret.
+#[cfg(any(feature = "aes-small", test))]
+fn mul_ct(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+/// Returns the multiplicative inverse of `a` as a^254, with 0 mapped to 0
+/// as the S-box construction expects. The exponent is fixed and every
+/// multiplication is [`mul_ct`], so the time taken does not depend on `a`.
+#[cfg(any(feature = "aes-small", test))]
+pub(super) fn mul_inverse(a: u8) -> u8 {
+    // Square and multiply over the bits of 254 = 0b11111110.
+    let mut result = 1u8;
+    let mut power = a;
+    for bit in 0..8 {
+        if (254 >> bit) & 1 == 1 {
+            result = mul_ct(result, power);
+        }
+        power = mul_ct(power, power);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xtime() {
+        // FIPS-197 section 4.2.1.
+        assert_eq!(xtime(0x57), 0xae);
+        assert_eq!(xtime(0xae), 0x47);
+        assert_eq!(xtime(0x47), 0x8e);
+        assert_eq!(xtime(0x8e), 0x07);
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x57, 0x13), 0xfe);
+
+        for a in 0..=255u8 {
+            assert_eq!(mul(a, 0), 0);
+            assert_eq!(mul(a, 1), a);
+            assert_eq!(mul(a, 2), xtime(a));
+            for b in [0x03, 0x1b, 0x80, 0xff] {
+                assert_eq!(mul(a, b), mul(b, a));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_ct() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(mul_ct(a, b), mul(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_inverse() {
+        assert_eq!(mul_inverse(0), 0);
+        assert_eq!(mul_inverse(1), 1);
+        assert_eq!(mul_inverse(0x53), 0xca);
+
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, mul_inverse(a)), 1);
+            assert_eq!(mul_inverse(mul_inverse(a)), a);
+            for b in [0x02, 0x57, 0xfe] {
+                assert_eq!(mul_inverse(mul(a, b)), mul(mul_inverse(a), mul_inverse(b)));
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 mod cipher;
+mod galois;
+pub mod modes;
 mod s_box;
 
 pub use cipher::Aes;
@@ -0,0 +1,136 @@
+//! The generic [modes of operation](crate::modes) specialized to AES.
+
+use super::Aes;
+use crate::modes;
+
+/// AES in CBC mode, taking a 16-byte IV and block-aligned input.
+pub type Cbc = modes::Cbc<Aes>;
+
+/// AES in CTR mode, taking a 16-byte counter block.
+///
+/// [`Ctr::new`](modes::Ctr::new) increments the whole block as one 128-bit
+/// big-endian number, as in SP 800-38A. For a fixed nonce with a narrower
+/// counter, use [`Ctr::with_nonce_counter`](modes::Ctr::with_nonce_counter),
+/// e.g. a 64-bit nonce and a 64-bit counter, or [`Aes::ctr_gcm_style`] for
+/// the 32-bit counter of GCM.
+pub type Ctr = modes::Ctr<Aes>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    const KEY: [u32; 4] = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+
+    /// Plaintext shared by the SP 800-38A examples.
+    const PLAINTEXT: [u128; 4] = [
+        0x6bc1bee2_2e409f96_e93d7e11_7393172a,
+        0xae2d8a57_1e03ac9c_9eb76fac_45af8e51,
+        0x30c81c46_a35ce411_e5fbc119_1a0a52ef,
+        0xf69f2445_df4f9b17_ad2b417b_e66c3710,
+    ];
+
+    fn to_bytes(blocks: &[u128]) -> Vec<u8> {
+        blocks
+            .iter()
+            .flat_map(|block| block.to_be_bytes())
+            .collect()
+    }
+
+    /// NIST SP 800-38A F.2.1 and F.2.2, CBC-AES128.
+    #[test]
+    fn test_cbc_sp_800_38a() {
+        let iv = 0x00010203_04050607_08090a0b_0c0d0e0fu128.to_be_bytes();
+        let expected = to_bytes(&[
+            0x7649abac_8119b246_cee98e9b_12e9197d,
+            0x5086cb9b_507219ee_95db113a_917678b2,
+            0x73bed6b8_e3c1743b_7116e69e_22229516,
+            0x3ff1caa1_681fac09_120eca30_7586e1a7,
+        ]);
+
+        let mut buf = to_bytes(&PLAINTEXT);
+        Cbc::new(Aes::new(&KEY).unwrap(), &iv)
+            .unwrap()
+            .encrypt(&mut buf)
+            .unwrap();
+        assert_eq!(buf, expected);
+
+        Cbc::new(Aes::new(&KEY).unwrap(), &iv)
+            .unwrap()
+            .decrypt(&mut buf)
+            .unwrap();
+        assert_eq!(buf, to_bytes(&PLAINTEXT));
+    }
+
+    #[test]
+    fn test_cbc_rejects_bad_input() {
+        for iv_len in [0, 8, 15, 17] {
+            assert!(matches!(
+                Cbc::new(Aes::new(&KEY).unwrap(), &vec![0u8; iv_len]),
+                Err(Error::InvalidLength)
+            ));
+        }
+
+        let mut cbc = Cbc::new(Aes::new(&KEY).unwrap(), &[0u8; 16]).unwrap();
+        assert_eq!(cbc.encrypt(&mut [0u8; 24]), Err(Error::UnalignedInput));
+        assert_eq!(cbc.decrypt(&mut [0u8; 8]), Err(Error::UnalignedInput));
+    }
+
+    /// NIST SP 800-38A F.5.1 and F.5.2, CTR-AES128.
+    #[test]
+    fn test_ctr_sp_800_38a() {
+        let counter_block = 0xf0f1f2f3_f4f5f6f7_f8f9fafb_fcfdfeffu128.to_be_bytes();
+        let expected = to_bytes(&[
+            0x874d6191_b620e326_1bef6864_990db6ce,
+            0x9806f66b_7970fdff_8617187b_b9fffdff,
+            0x5ae4df3e_dbd5d35e_5b4f0902_0db03eab,
+            0x1e031dda_2fbe03d1_792170a0_f3009cee,
+        ]);
+
+        // Uneven chunks run through partial blocks across calls.
+        let mut buf = to_bytes(&PLAINTEXT);
+        let mut ctr = Ctr::new(Aes::new(&KEY).unwrap(), &counter_block).unwrap();
+        for chunk in buf.chunks_mut(7) {
+            ctr.apply_keystream(chunk);
+        }
+        assert_eq!(buf, expected);
+        // The low byte 0xff carried into the next one after the first block.
+        assert_eq!(ctr.counter(), 0xf0f1f2f3_f4f5f6f7_f8f9fafb_fcfdff03);
+
+        Ctr::new(Aes::new(&KEY).unwrap(), &counter_block)
+            .unwrap()
+            .apply_keystream(&mut buf);
+        assert_eq!(buf, to_bytes(&PLAINTEXT));
+    }
+
+    #[test]
+    fn test_ctr_carry_and_wraparound() {
+        let aes = || Aes::new(&KEY).unwrap();
+        let keystream_of = |counter: u128| aes().encrypt(counter.to_be_bytes());
+
+        // A full-width counter carries across the 32 and 64-bit boundaries.
+        let start = 0x00000000_0000000f_ffffffff_ffffffffu128;
+        let mut ctr = Ctr::new(aes(), &start.to_be_bytes()).unwrap();
+        let mut buf = [0u8; 32];
+        ctr.apply_keystream(&mut buf);
+        assert_eq!(buf[..16], keystream_of(start));
+        assert_eq!(
+            buf[16..],
+            keystream_of(0x00000000_00000010_00000000_00000000)
+        );
+
+        // A 64-bit counter wraps to zero without touching the nonce.
+        let nonce = 0x01234567_89abcdefu128;
+        let mut ctr = Ctr::with_nonce_counter(aes(), 64, nonce, u64::MAX as u128).unwrap();
+        let mut buf = [0u8; 32];
+        ctr.apply_keystream(&mut buf);
+        assert_eq!(buf[..16], keystream_of(nonce << 64 | u64::MAX as u128));
+        assert_eq!(buf[16..], keystream_of(nonce << 64));
+        assert_eq!(ctr.counter(), nonce << 64 | 1);
+
+        // The whole 128-bit counter wraps around too.
+        let mut ctr = Ctr::new(aes(), &[0xff; 16]).unwrap();
+        ctr.apply_keystream(&mut [0u8; 16]);
+        assert_eq!(ctr.counter(), 0);
+    }
+}
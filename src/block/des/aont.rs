@@ -0,0 +1,127 @@
+//! A DES-based all-or-nothing transform, after Rivest's package transform.
+//!
+//! The data is encrypted in CTR mode under a random one-time key, and the key
+//! is appended XORed with a hash of every ciphertext block. Without all of the
+//! blocks the key cannot be recovered, so no part of the data can be read.
+//! The transform is keyless: anyone holding the whole package can decode it.
+
+use alloc::vec::Vec;
+use rand_core::RngCore;
+
+use super::Des;
+use crate::Error;
+use crate::constant_time::ct_eq;
+use crate::modes::Ctr;
+
+/// Public key of the block hash. It only needs to be fixed, not secret.
+const HASH_KEY: u64 = 0x4A6F_6B65_7273_2121;
+
+/// Zero block appended to the data, so decoding a damaged package is detected.
+const CANARY: [u8; 8] = [0u8; 8];
+
+/// Wraps `data` into a package of `data.len() + 16` bytes: the ciphertext of
+/// `data || canary` followed by the masked one-time key drawn from `rng`.
+pub fn encode<R: RngCore>(rng: &mut R, data: &[u8]) -> Vec<u8> {
+    let mut key = [0u8; 8];
+    rng.fill_bytes(&mut key);
+
+    let mut package = Vec::with_capacity(data.len() + 16);
+    package.extend_from_slice(data);
+    package.extend_from_slice(&CANARY);
+    ctr(key).apply_keystream(&mut package);
+
+    let masked_key = u64::from_be_bytes(key) ^ hash(&package);
+    package.extend_from_slice(&masked_key.to_be_bytes());
+    package
+}
+
+/// Recovers the data from a package produced by [`encode`].
+///
+/// Fails with [`Error::AuthenticationFailed`] when any part of the package
+/// is missing or altered.
+pub fn decode(package: &[u8]) -> Result<Vec<u8>, Error> {
+    if package.len() < 16 {
+        return Err(Error::InvalidLength);
+    }
+    let (ciphertext, masked_key) = package.split_at(package.len() - 8);
+
+    let masked_key = u64::from_be_bytes(masked_key.try_into().unwrap());
+    let key = (masked_key ^ hash(ciphertext)).to_be_bytes();
+
+    let mut data = ciphertext.to_vec();
+    ctr(key).apply_keystream(&mut data);
+
+    let (data_len, canary) = (data.len() - 8, &data[data.len() - 8..]);
+    if !ct_eq(canary, &CANARY) {
+        return Err(Error::AuthenticationFailed);
+    }
+    data.truncate(data_len);
+    Ok(data)
+}
+
+fn ctr(key: [u8; 8]) -> Ctr<Des> {
+    Ctr::new(Des::from_bytes(key), &[0u8; 8]).expect("counter block is 8 bytes")
+}
+
+/// XORs together `E(HASH_KEY, block_i ^ i)` over the zero-padded ciphertext blocks,
+/// numbering them from 1 so that moving a block changes the hash.
+fn hash(ciphertext: &[u8]) -> u64 {
+    let hasher = Des::new(HASH_KEY);
+    ciphertext.chunks(8).zip(1u64..).fold(0, |acc, (chunk, i)| {
+        let mut block = [0u8; 8];
+        block[..chunk.len()].copy_from_slice(chunk);
+        acc ^ hasher.encrypt(u64::from_be_bytes(block) ^ i)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const MESSAGE: &[u8] = b"all of it or none of it, forty bytes....";
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = StdRng::seed_from_u64(504);
+
+        for len in [0, 1, 8, 13, MESSAGE.len()] {
+            let package = encode(&mut rng, &MESSAGE[..len]);
+            assert_eq!(package.len(), len + 16);
+            assert_eq!(decode(&package).unwrap(), &MESSAGE[..len]);
+        }
+    }
+
+    #[test]
+    fn test_random_key() {
+        let mut rng = StdRng::seed_from_u64(504);
+        assert_ne!(encode(&mut rng, MESSAGE), encode(&mut rng, MESSAGE));
+    }
+
+    #[test]
+    fn test_dropping_any_block_fails() {
+        let mut rng = StdRng::seed_from_u64(504);
+        let package = encode(&mut rng, MESSAGE);
+
+        for start in (0..package.len()).step_by(8) {
+            let mut damaged = package.clone();
+            damaged.drain(start..start + 8);
+            assert_eq!(
+                decode(&damaged),
+                Err(Error::AuthenticationFailed),
+                "block at {}",
+                start
+            );
+        }
+    }
+
+    #[test]
+    fn test_altered_block_fails() {
+        let mut rng = StdRng::seed_from_u64(504);
+        let mut package = encode(&mut rng, MESSAGE);
+        package[3] ^= 1;
+        assert_eq!(decode(&package), Err(Error::AuthenticationFailed));
+        assert_eq!(decode(&package[..15]), Err(Error::InvalidLength));
+    }
+}
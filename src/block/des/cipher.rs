@@ -1,7 +1,28 @@
-use super::permutation_tables::{E, FINAL_PERMUTATION, INITIAL_PERMUTATION, P, PC_1, PC_2};
+#[cfg(any(feature = "constant-time", test))]
+use super::permutation_tables::P;
+use super::permutation_tables::{E, FINAL_PERMUTATION, INITIAL_PERMUTATION, PC_1, PC_2};
+#[cfg(any(feature = "constant-time", test))]
 use super::s_boxes::S;
+#[cfg(any(not(feature = "constant-time"), test))]
+use super::sp_boxes::SP;
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::modes::Mode;
+use crate::padding::{pkcs7_pad, pkcs7_unpad};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 /// Data Encryption Standard
+///
+/// With the `serde` feature it serializes as its 16 round keys, so restoring it
+/// skips the key schedule. Round keys wider than 48 bits are rejected.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "[u64; 16]", into = "[u64; 16]")
+)]
 pub struct Des {
     round_keys: [u64; 16],
 }
@@ -34,18 +55,166 @@ impl Des {
         Self { round_keys }
     }
 
+    /// Creates a `Des` from a key given as bytes, read big-endian like [`Des::new`]'s `u64`.
+    pub fn from_bytes(key: [u8; 8]) -> Self {
+        Self::new(u64::from_be_bytes(key))
+    }
+
+    /// Creates a `Des` from a key stored as two 32-bit words, `hi` being the top half.
+    pub fn from_words(hi: u32, lo: u32) -> Self {
+        Self::new(((hi as u64) << 32) | lo as u64)
+    }
+
+    /// Returns how many DES block operations `len` bytes of input take in `mode`,
+    /// counting the extra padding block of ECB and CBC.
+    pub fn block_count(len: usize, mode: Mode) -> usize {
+        mode.block_count(len, 8)
+    }
+
+    /// Reports whether both ciphers have the same key schedule.
+    /// Keys that differ only in their parity bits share one effective 56-bit key.
+    pub fn schedule_matches(&self, other: &Des) -> bool {
+        self.round_keys == other.round_keys
+    }
+
+    /// Creates a `Des` from 16 precomputed 48-bit round keys, e.g. subkeys
+    /// extracted from hardware, skipping the key schedule entirely.
+    pub fn from_round_keys(round_keys: [u64; 16]) -> Result<Self, Error> {
+        if round_keys.iter().any(|&k| k >= 1u64 << 48) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(Self { round_keys })
+    }
+
+    /// Returns the 16 round keys, in encryption order.
+    pub fn round_keys(&self) -> [u64; 16] {
+        self.round_keys
+    }
+
     /// Encrypt any given 64-bit block of text.
     pub fn encrypt(&self, plain_block: u64) -> u64 {
-        self.apply_round_keys(plain_block, 0..16)
+        self.apply_round_keys(plain_block, 0..16, apply_f)
     }
 
     /// Decrypt any given 64-bit block of text.
     pub fn decrypt(&self, cipher_block: u64) -> u64 {
-        self.apply_round_keys(cipher_block, (0..=15).rev())
+        self.apply_round_keys(cipher_block, (0..=15).rev(), apply_f)
+    }
+
+    /// Encrypts `block`, or decrypts it when `decrypt` is true, without branching
+    /// on `decrypt`. Decryption walks the round keys backwards, so round `i` uses
+    /// key `i ^ 15`; the flag is turned into a mask that is XORed into every index.
+    pub fn crypt_ct(&self, block: u64, decrypt: bool) -> u64 {
+        let flip = core::hint::black_box(decrypt as usize).wrapping_neg() & 15;
+        self.apply_round_keys(block, (0..16).map(|i| i ^ flip), apply_f)
+    }
+
+    /// Encrypts an 8-byte block, read and written big-endian.
+    /// Unlike [`BlockCipher::encrypt_block`], this takes and returns the block by value.
+    pub fn encrypt_bytes(&self, block: [u8; 8]) -> [u8; 8] {
+        self.encrypt(u64::from_be_bytes(block)).to_be_bytes()
+    }
+
+    /// Decrypts an 8-byte block produced by [`Des::encrypt_bytes`].
+    pub fn decrypt_bytes(&self, block: [u8; 8]) -> [u8; 8] {
+        self.decrypt(u64::from_be_bytes(block)).to_be_bytes()
+    }
+
+    /// Derives an 8-byte nonce by encrypting `counter`.
+    /// DES is a permutation, so distinct counters under one key never give the same nonce.
+    pub fn derive_nonce(&self, counter: u64) -> [u8; 8] {
+        self.encrypt(counter).to_be_bytes()
+    }
+
+    /// Pads `data` with PKCS#7 and encrypts every 8-byte block independently (ECB mode).
+    ///
+    /// ECB is insecure for almost any real data: identical plaintext blocks give
+    /// identical ciphertext blocks, so patterns in the data show through.
+    /// Prefer CBC or CTR from [`crate::modes`].
+    pub fn encrypt_ecb(&self, data: &[u8]) -> Vec<u8> {
+        let mut buf = data.to_vec();
+        pkcs7_pad(&mut buf, 8);
+        for block in buf.chunks_exact_mut(8) {
+            self.encrypt_block(block);
+        }
+        buf
+    }
+
+    /// Same as [`Des::encrypt_ecb`], but encrypts the blocks on the rayon thread pool.
+    /// Shares the insecurity of ECB mode.
+    #[cfg(feature = "rayon")]
+    pub fn encrypt_ecb_par(&self, data: &[u8]) -> Vec<u8> {
+        crate::block::encrypt_ecb_par(self, data)
+    }
+
+    /// Decrypts ECB ciphertext from [`Des::encrypt_ecb`] and strips its PKCS#7 padding.
+    /// Shares the insecurity of ECB mode.
+    pub fn decrypt_ecb(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        if !data.len().is_multiple_of(8) {
+            return Err(Error::UnalignedInput);
+        }
+
+        let mut buf = data.to_vec();
+        for block in buf.chunks_exact_mut(8) {
+            self.decrypt_block(block);
+        }
+        pkcs7_unpad(&mut buf, 8)?;
+        Ok(buf)
+    }
+
+    /// Encrypts an 8-byte `block` into exactly 16 uppercase hex characters,
+    /// as transmitted by some legacy text protocols.
+    pub fn encrypt_block_hex(&self, block: &[u8]) -> Result<String, Error> {
+        let block: [u8; 8] = block.try_into().map_err(|_| Error::InvalidLength)?;
+        Ok(format!("{:016X}", self.encrypt(u64::from_be_bytes(block))))
+    }
+
+    /// Decrypts a block of 16 hex characters produced by [`Des::encrypt_block_hex`].
+    pub fn decrypt_block_hex(&self, hex: &str) -> Result<[u8; 8], Error> {
+        if hex.len() != 16 {
+            return Err(Error::InvalidLength);
+        }
+        if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::InvalidHex);
+        }
+
+        let block = u64::from_str_radix(hex, 16).map_err(|_| Error::InvalidHex)?;
+        Ok(self.decrypt(block).to_be_bytes())
     }
 
-    /// Does encryption or decryption depending on the range order.
-    fn apply_round_keys<I>(&self, block: u64, range: I) -> u64
+    /// Applies the round function f to a 32-bit half-block with a 48-bit round key.
+    /// Exposed for cryptanalysis, e.g. building difference distribution tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `right` is wider than 32 bits or `round_key` wider than 48 bits.
+    pub fn round_function(right: u64, round_key: u64) -> u64 {
+        assert!(right <= MASK_RIGHT_32_BIT, "right must fit into 32 bits");
+        assert!(round_key < 1u64 << 48, "round_key must fit into 48 bits");
+        apply_f(right, round_key)
+    }
+
+    /// Encrypts `block` the way crypt(3) does: for every bit `i` set in the
+    /// 12-bit `salt`, outputs `i` and `i + 24` of the E expansion trade places.
+    /// A zero salt gives plain DES.
+    pub(super) fn encrypt_salted(&self, block: u64, salt: u16) -> u64 {
+        let salt = salt as u64 & 0xFFF;
+        // Bits 47 - i and 23 - i hold E outputs i and i + 24, counting from the left.
+        let mask = (0..12)
+            .filter(|i| (salt >> i) & 1 == 1)
+            .fold(0u64, |mask, i| mask | 1 << (23 - i));
+
+        self.apply_round_keys(block, 0..16, |right, round_key| {
+            let expanded = permutate(right, &E, 32);
+            let swapped = (expanded ^ (expanded >> 24)) & mask;
+            let salted = expanded ^ swapped ^ (swapped << 24);
+            substitute_permute(salted ^ round_key)
+        })
+    }
+
+    /// Does encryption or decryption depending on the range order,
+    /// with `f` as the round function.
+    fn apply_round_keys<I>(&self, block: u64, range: I, f: impl Fn(u64, u64) -> u64) -> u64
     where
         I: IntoIterator<Item = usize>,
     {
@@ -56,7 +225,7 @@ impl Des {
 
         // Twist halves and apply f function.
         for i in range {
-            (left, right) = (right, left ^ apply_f(right, self.round_keys[i]));
+            (left, right) = (right, left ^ f(right, self.round_keys[i]));
         }
 
         (left, right) = (right, left);
@@ -67,16 +236,79 @@ impl Des {
     }
 }
 
+impl TryFrom<[u64; 16]> for Des {
+    type Error = Error;
+
+    fn try_from(round_keys: [u64; 16]) -> Result<Self, Error> {
+        Des::from_round_keys(round_keys)
+    }
+}
+
+impl From<Des> for [u64; 16] {
+    fn from(des: Des) -> Self {
+        des.round_keys
+    }
+}
+
+impl BlockCipher for Des {
+    const BLOCK_SIZE: usize = 8;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let b: [u8; 8] = block.try_into().expect("DES block must be 8 bytes");
+        block.copy_from_slice(&self.encrypt_bytes(b));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let b: [u8; 8] = block.try_into().expect("DES block must be 8 bytes");
+        block.copy_from_slice(&self.decrypt_bytes(b));
+    }
+}
+
+/// Encrypts `block` under each key in turn, building every `Des` once.
+/// With no keys the block is returned unchanged.
+pub fn encrypt_with_keys(keys: &[u64], block: u64) -> u64 {
+    keys.iter()
+        .fold(block, |block, &k| Des::new(k).encrypt(block))
+}
+
+/// Performs one Feistel step of DES: returns `(right, left ^ f(right, round_key))`.
+/// Only the low 48 bits of `round_key` are used, so any input is valid for fuzzing.
+pub fn feistel_round(left: u32, right: u32, round_key: u64) -> (u32, u32) {
+    let f = apply_f(right as u64, round_key & ((1u64 << 48) - 1));
+    (right, left ^ f as u32)
+}
+
 /// Applies the DES round function f to a 32-bit half-block
 /// using the given round key.
-/// TODO: test
 fn apply_f(right: u64, round_key: u64) -> u64 {
-    // Expand 32-bit half-block into 48 bits.
-    let expanded: u64 = permutate(right, &E, 32);
+    // Expand 32-bit half-block into 48 bits and mix with the round key.
+    let keyed: u64 = permutate(right, &E, 32) ^ round_key;
+
+    substitute_permute(keyed)
+}
+
+/// Runs the S-boxes and P over an expanded, keyed 48-bit half-block.
+fn substitute_permute(keyed: u64) -> u64 {
+    #[cfg(feature = "constant-time")]
+    return substitute_permute_split(keyed);
 
-    // Mix with the round key.
-    let keyed: u64 = expanded ^ round_key;
+    #[cfg(not(feature = "constant-time"))]
+    return substitute_permute_fused(keyed);
+}
 
+/// One lookup per S-box into the fused [`SP`] tables, which already hold
+/// each S-box output permuted by P.
+#[cfg(any(not(feature = "constant-time"), test))]
+fn substitute_permute_fused(keyed: u64) -> u64 {
+    split_6bit_chunks(keyed)
+        .iter()
+        .zip(&SP)
+        .fold(0, |f, (&chunk, table)| f | table[chunk as usize]) as u64
+}
+
+/// S-box substitution, then the P permutation, step by step.
+#[cfg(any(feature = "constant-time", test))]
+fn substitute_permute_split(keyed: u64) -> u64 {
     // Split encrypted block into eight 6-bit chunks.
     let mut chunks: [u64; 8] = split_6bit_chunks(keyed);
 
@@ -93,6 +325,10 @@ fn apply_f(right: u64, round_key: u64) -> u64 {
 
 /// Takes bits from the input key `k` at positions specified in permutation vector,
 /// and writes them sequentially into the output key.
+///
+/// Panics if `k` does not fit into `k_size` bits. Public entry points only pass
+/// full 64-bit blocks or values they produced themselves, so this is never reachable
+/// from user input.
 fn permutate(k: u64, permutation_vec: &[u8], k_size: u8) -> u64 {
     if k_size < 64 {
         assert!(k < (1u64 << k_size), "k is bigger than declared k_size");
@@ -139,10 +375,37 @@ fn rotate_left(k: u64, n: usize) -> u64 {
 }
 
 /// For a given 6-bit `chunk` returns a 4-bit output from the `s_box`.
+#[cfg(any(feature = "constant-time", test))]
 fn substitute(chunk: u64, s_box: &[u8; 64]) -> u64 {
+    #[cfg(feature = "constant-time")]
+    return substitute_ct(chunk, s_box);
+
+    #[cfg(not(feature = "constant-time"))]
+    return substitute_table(chunk, s_box);
+}
+
+/// Looks the S-box output up directly, indexing the table with secret data.
+#[cfg(test)]
+fn substitute_table(chunk: u64, s_box: &[u8; 64]) -> u64 {
+    s_box[s_box_index(chunk)] as u64
+}
+
+/// Reads all 64 S-box entries and keeps the matching one through a mask, so the
+/// memory access pattern does not depend on `chunk`.
+#[cfg(any(feature = "constant-time", test))]
+fn substitute_ct(chunk: u64, s_box: &[u8; 64]) -> u64 {
+    let index = s_box_index(chunk);
+    s_box.iter().enumerate().fold(0u8, |acc, (i, &entry)| {
+        crate::constant_time::ct_select(i == index, entry, acc)
+    }) as u64
+}
+
+/// Maps a 6-bit chunk to its S-box position: the outer bits pick the row and
+/// the inner four bits the column.
+pub(super) const fn s_box_index(chunk: u64) -> usize {
     let row = ((chunk >> 4) & 0b10) | (chunk & 1);
     let col = (chunk & 0b11111) >> 1;
-    s_box[(16 * row + col) as usize] as u64
+    (16 * row + col) as usize
 }
 
 /// Splits 48-bit value into eight 6-bit chunks.
@@ -151,6 +414,7 @@ fn split_6bit_chunks(block: u64) -> [u64; 8] {
 }
 
 /// Merges 4-bit chunks into one 32-bit value.
+#[cfg(any(feature = "constant-time", test))]
 fn merge_4bit_chunks(chunks: [u64; 8]) -> u64 {
     let mut result: u64 = 0;
     for chunk in chunks {
@@ -202,6 +466,329 @@ mod tests {
         // 3. output is always the same with same inputs
     }
 
+    #[test]
+    fn test_encrypt_with_keys() {
+        let (k1, k2) = (0x1334_5779_9BBC_DFF1, 0x0E32_9232_EA6D_0D73);
+        let block = 0x0123_4567_89AB_CDEF;
+
+        assert_eq!(encrypt_with_keys(&[], block), block);
+        assert_eq!(encrypt_with_keys(&[k1], block), Des::new(k1).encrypt(block));
+        assert_eq!(
+            encrypt_with_keys(&[k1, k2], block),
+            Des::new(k2).encrypt(Des::new(k1).encrypt(block))
+        );
+        assert_ne!(
+            encrypt_with_keys(&[k1, k2], block),
+            encrypt_with_keys(&[k2, k1], block)
+        );
+    }
+
+    #[test]
+    fn test_block_count() {
+        // Aligned input still gets a full padding block.
+        assert_eq!(Des::block_count(0, Mode::Ecb), 1);
+        assert_eq!(Des::block_count(16, Mode::Ecb), 3);
+        assert_eq!(Des::block_count(17, Mode::Ecb), 3);
+        assert_eq!(Des::block_count(16, Mode::Cbc), 3);
+
+        // Stream modes only cover the input.
+        assert_eq!(Des::block_count(0, Mode::Ctr), 0);
+        assert_eq!(Des::block_count(16, Mode::Ctr), 2);
+        assert_eq!(Des::block_count(17, Mode::Ctr), 3);
+        assert_eq!(Des::block_count(1, Mode::Cfb), 1);
+    }
+
+    #[test]
+    fn test_from_words() {
+        let (hi, lo) = (0x1334_5779u32, 0x9BBC_DFF1u32);
+        let cipher = Des::from_words(hi, lo);
+
+        assert!(cipher.schedule_matches(&Des::new(((hi as u64) << 32) | lo as u64)));
+        assert_eq!(cipher.encrypt(0x0123_4567_89AB_CDEF), 0x85E8_1354_0F0A_B405);
+    }
+
+    #[test]
+    fn test_schedule_matches() {
+        let key: u64 = 0x1334_5779_9BBC_DFF1;
+        let cipher = Des::new(key);
+
+        // Flipping parity bits does not change the effective key.
+        assert!(cipher.schedule_matches(&Des::new(key ^ 0x0101_0101_0101_0101)));
+        assert!(cipher.schedule_matches(&Des::new(key ^ 0x0001_0000_0100_0001)));
+
+        assert!(!cipher.schedule_matches(&Des::new(key ^ 0x0200_0000_0000_0000)));
+        assert!(!cipher.schedule_matches(&Des::new(!key)));
+    }
+
+    #[test]
+    fn test_block_hex() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        let block = 0x0123_4567_89AB_CDEFu64.to_be_bytes();
+
+        let hex = cipher.encrypt_block_hex(&block).unwrap();
+        assert_eq!(hex, "85E813540F0AB405");
+        assert_eq!(cipher.decrypt_block_hex(&hex).unwrap(), block);
+
+        // Leading zeros are kept so the width never changes.
+        for plaintext in [0u64, 1, u64::MAX, 0x00FF] {
+            let hex = cipher.encrypt_block_hex(&plaintext.to_be_bytes()).unwrap();
+            assert_eq!(hex.len(), 16);
+            assert_eq!(
+                cipher.decrypt_block_hex(&hex).unwrap(),
+                plaintext.to_be_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_hex_errors() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+
+        assert_eq!(
+            cipher.encrypt_block_hex(&[0u8; 7]),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            cipher.decrypt_block_hex("85E813540F0AB4"),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            cipher.decrypt_block_hex("+5E813540F0AB405"),
+            Err(Error::InvalidHex)
+        );
+        assert_eq!(
+            cipher.decrypt_block_hex("85E813540F0AB40G"),
+            Err(Error::InvalidHex)
+        );
+    }
+
+    #[test]
+    fn test_public_api_rejects_malformed_input() {
+        let cipher = Des::new(u64::MAX);
+
+        // Full-width values never trip the permutation size check.
+        cipher.decrypt(cipher.encrypt(u64::MAX));
+
+        assert_eq!(
+            cipher.encrypt_block_hex(&[0u8; 9]),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(cipher.encrypt_block_hex(&[]), Err(Error::InvalidLength));
+        assert_eq!(
+            cipher.decrypt_block_hex("FFFFFFFFFFFFFFFFF"),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(cipher.decrypt_block_hex("ÿÿÿÿÿÿÿÿ"), Err(Error::InvalidHex));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let key = 0x1334_5779_9BBC_DFF1u64;
+        let block = 0x0123_4567_89AB_CDEFu64;
+        let cipher = Des::from_bytes(key.to_be_bytes());
+
+        assert!(cipher.schedule_matches(&Des::new(key)));
+        assert_eq!(
+            cipher.encrypt_bytes(block.to_be_bytes()),
+            Des::new(key).encrypt(block).to_be_bytes()
+        );
+        assert_eq!(
+            cipher.decrypt_bytes(cipher.encrypt_bytes(block.to_be_bytes())),
+            block.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_ecb() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        let block = *b"ECB leak";
+        let mut repeated = block.repeat(2);
+        repeated.extend_from_slice(b"tail");
+
+        // The hallmark of ECB: identical blocks encrypt identically.
+        let ciphertext = cipher.encrypt_ecb(&repeated);
+        assert_eq!(ciphertext.len(), 24);
+        assert_eq!(ciphertext[..8], ciphertext[8..16]);
+        assert_eq!(ciphertext[..8], cipher.encrypt_bytes(block));
+
+        assert_eq!(cipher.decrypt_ecb(&ciphertext).unwrap(), repeated);
+        assert_eq!(
+            cipher.decrypt_ecb(&ciphertext[..20]),
+            Err(Error::UnalignedInput)
+        );
+    }
+
+    #[test]
+    fn test_from_round_keys() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        let rebuilt = Des::from_round_keys(cipher.round_keys()).unwrap();
+
+        assert!(rebuilt.schedule_matches(&cipher));
+        assert_eq!(
+            rebuilt.encrypt(0x0123_4567_89AB_CDEF),
+            0x85E8_1354_0F0A_B405
+        );
+        assert_eq!(
+            rebuilt.decrypt(0x85E8_1354_0F0A_B405),
+            0x0123_4567_89AB_CDEF
+        );
+
+        let mut too_wide = cipher.round_keys();
+        too_wide[15] |= 1 << 48;
+        assert!(matches!(
+            Des::from_round_keys(too_wide),
+            Err(Error::OutOfRange)
+        ));
+    }
+
+    /// The SP 800-20 Monte Carlo test for TECB with keying option 3 (all three
+    /// keys equal, which is single DES): 400 outer iterations of 10,000 chained
+    /// encryptions. After each, the last ciphertext is XORed into the key, the
+    /// key gets odd parity again, and the ciphertext becomes the next plaintext.
+    /// The recorded ciphertexts were reproduced with OpenSSL through Python's
+    /// `cryptography`. The constant-time S-boxes are too slow for all 400
+    /// iterations in a debug build, so with them only the first four run.
+    #[test]
+    fn test_monte_carlo() {
+        use crate::block::des::set_key_parity;
+
+        let iterations = if cfg!(feature = "constant-time") {
+            4
+        } else {
+            400
+        };
+        let mut key = 0x0123_4567_89AB_CDEF_u64;
+        let mut block = 0x4E6F_7720_6973_2074;
+        let mut records = Vec::new();
+        for _ in 0..iterations {
+            let cipher = Des::new(key);
+            for _ in 0..10_000 {
+                block = cipher.encrypt(block);
+            }
+            records.push(block);
+
+            let mut key_bytes = (key ^ block).to_be_bytes();
+            set_key_parity(&mut key_bytes);
+            key = u64::from_be_bytes(key_bytes);
+        }
+
+        assert_eq!(
+            records[..4],
+            [
+                0x6A2A_19F4_1ECA_854B,
+                0xCE5D_6C7B_6317_7C18,
+                0xBA16_5FFA_0060_347C,
+                0x5C6A_CDDD_5B05_1D1E,
+            ]
+        );
+        if iterations == 400 {
+            assert_eq!(records[399], 0x74D8_A695_064E_C574);
+            assert_eq!(key, 0xB62A_EA52_DC26_3451);
+        }
+    }
+
+    #[test]
+    fn test_crypt_ct() {
+        use rand::rngs::StdRng;
+        use rand::{RngCore, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(514);
+        for _ in 0..100 {
+            let cipher = Des::new(rng.next_u64());
+            let block = rng.next_u64();
+            assert_eq!(cipher.crypt_ct(block, false), cipher.encrypt(block));
+            assert_eq!(cipher.crypt_ct(block, true), cipher.decrypt(block));
+        }
+    }
+
+    #[test]
+    fn test_feistel_round() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        let block = 0x0123_4567_89AB_CDEF;
+
+        let ip_block = permutate(block, &INITIAL_PERMUTATION, 64);
+        let (mut left, mut right) = ((ip_block >> 32) as u32, ip_block as u32);
+        for &round_key in &cipher.round_keys() {
+            (left, right) = feistel_round(left, right, round_key);
+        }
+        let merged = (right as u64) << 32 | left as u64;
+
+        assert_eq!(
+            permutate(merged, &FINAL_PERMUTATION, 64),
+            cipher.encrypt(block)
+        );
+
+        // Bits above the 48-bit round key are ignored.
+        assert_eq!(
+            feistel_round(1, 2, 0xFFFF_0000_0000_0042),
+            feistel_round(1, 2, 0x42)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        let json = serde_json::to_string(&cipher).unwrap();
+        let restored: Des = serde_json::from_str(&json).unwrap();
+        assert!(restored.schedule_matches(&cipher));
+
+        let mut too_wide = cipher.round_keys();
+        too_wide[0] = 1 << 48;
+        let json = serde_json::to_string(&too_wide).unwrap();
+        assert!(serde_json::from_str::<Des>(&json).is_err());
+    }
+
+    #[test]
+    fn test_derive_nonce() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        assert_eq!(
+            cipher.derive_nonce(0x0123_4567_89AB_CDEF),
+            0x85E8_1354_0F0A_B405u64.to_be_bytes()
+        );
+
+        let nonces: Vec<[u8; 8]> = (0..1000).map(|c| cipher.derive_nonce(c)).collect();
+        let mut unique = nonces.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), nonces.len());
+
+        for (counter, nonce) in (0..1000).zip(&nonces) {
+            assert_eq!(&cipher.derive_nonce(counter), nonce);
+        }
+    }
+
+    #[test]
+    fn test_round_function() {
+        let cipher = Des::new(0x1334_5779_9BBC_DFF1);
+        let inputs = [0u64, 1, 0xF0AA_F0AA, 0xFFFF_FFFF, 0xDEAD_BEEF];
+
+        for (right, round_key) in inputs.into_iter().zip(cipher.round_keys) {
+            assert_eq!(
+                Des::round_function(right, round_key),
+                apply_f(right, round_key)
+            );
+        }
+
+        // Round 1 of the classic worked example.
+        assert_eq!(
+            Des::round_function(0xF0AA_F0AA, 0x1B02_EFFC_7072),
+            0x234A_A9BB
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "right must fit into 32 bits")]
+    fn test_round_function_wide_right() {
+        Des::round_function(1 << 32, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "round_key must fit into 48 bits")]
+    fn test_round_function_wide_round_key() {
+        Des::round_function(0, 1 << 48);
+    }
+
     #[test]
     #[should_panic(expected = "k is bigger than declared k_size")]
     fn test_permutate_panic() {
@@ -263,6 +850,56 @@ mod tests {
         assert_eq!(substitute(0b111011, &S[2]), 5, "s_box test 3");
     }
 
+    #[test]
+    fn test_fused_round_function_matches_split() {
+        use rand::rngs::StdRng;
+        use rand::{RngCore, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(539);
+        for _ in 0..1000 {
+            let keyed = rng.next_u64() & ((1u64 << 48) - 1);
+            assert_eq!(
+                substitute_permute_fused(keyed),
+                substitute_permute_split(keyed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_substitute_ct_matches_table() {
+        for s_box in &S {
+            for chunk in 0..64 {
+                assert_eq!(substitute_ct(chunk, s_box), substitute_table(chunk, s_box));
+            }
+        }
+    }
+
+    #[test]
+    fn test_s_box_rows_are_permutations() {
+        for (i, s_box) in S.iter().enumerate() {
+            for (row, entries) in s_box.chunks(16).enumerate() {
+                let mut seen = [false; 16];
+                for &value in entries {
+                    assert!(
+                        value < 16,
+                        "S{} row {} has out-of-range {}",
+                        i + 1,
+                        row,
+                        value
+                    );
+                    seen[value as usize] = true;
+                }
+                assert!(
+                    seen.iter().all(|&v| v),
+                    "S{} row {} is not a permutation of 0..=15: {:?}",
+                    i + 1,
+                    row,
+                    entries
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_merge_halves() {
         assert_eq!(merge_halves(0b10_1010, 0b01_1111, 6), 0b1010_1001_1111);
@@ -0,0 +1,83 @@
+use super::Des;
+use crate::Error;
+use alloc::string::String;
+
+/// The 64 characters of the crypt(3) salt and hash encoding, in value order.
+const ALPHABET: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Hashes `password` with the traditional Unix crypt(3), returning the classic
+/// 13 characters: the two salt characters followed by 11 characters of hash.
+///
+/// Only the low 7 bits of the first 8 password bytes count, shifted left to
+/// form a DES key. The key then encrypts a zero block 25 times with E modified
+/// by the 12-bit salt. `salt` must be exactly two characters from
+/// `./0-9A-Za-z`, otherwise [`Error::InvalidSalt`] is returned.
+///
+/// Eight characters and 4096 salts are nowhere near enough today; this exists
+/// for reading old password files, not for storing new passwords.
+pub fn crypt(password: &[u8], salt: &str) -> Result<String, Error> {
+    let salt_bytes: [u8; 2] = salt.as_bytes().try_into().map_err(|_| Error::InvalidSalt)?;
+    let [low, high] = salt_bytes.map(decode_char);
+    let salt_bits =
+        (low.ok_or(Error::InvalidSalt)? as u16) | (high.ok_or(Error::InvalidSalt)? as u16) << 6;
+
+    let key = (0..8).fold(0u64, |key, i| {
+        let byte = password.get(i).copied().unwrap_or(0);
+        (key << 8) | (byte << 1) as u64
+    });
+    let des = Des::new(key);
+    let hash = (0..25).fold(0u64, |block, _| des.encrypt_salted(block, salt_bits));
+
+    let mut out = String::with_capacity(13);
+    out.push_str(salt);
+    // 64 bits make 11 characters of 6 bits, the last one padded with two zero bits.
+    for i in 0..11 {
+        let sextet = ((hash as u128) << 2 >> (60 - 6 * i)) & 0x3F;
+        out.push(ALPHABET[sextet as usize] as char);
+    }
+    Ok(out)
+}
+
+/// Returns the 6-bit value of a salt character.
+fn decode_char(c: u8) -> Option<u8> {
+    ALPHABET.iter().position(|&a| a == c).map(|v| v as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference hashes from glibc's crypt(3).
+    #[test]
+    fn test_known_hashes() {
+        let cases: [(&[u8], &str, &str); 5] = [
+            (b"foo", "ab", "abQ9KY.KfrYrc"),
+            (b"password", "ZZ", "ZZKRwXSu3tt8s"),
+            (b"", "..", "..X8NBuQ4l6uQ"),
+            (b"\xff\x80abc", "9z", "9zPSVpxpsHUEo"),
+            // Only the first eight bytes count.
+            (b"test1234extra", "x/", "x/N6XF41OKzJc"),
+        ];
+
+        for (password, salt, expected) in cases {
+            assert_eq!(crypt(password, salt).unwrap(), expected);
+        }
+        assert_eq!(crypt(b"test1234", "x/").unwrap(), "x/N6XF41OKzJc");
+    }
+
+    #[test]
+    fn test_zero_salt_is_plain_des() {
+        let des = Des::new(0x0123_4567_89AB_CDEF);
+        for block in [0u64, 0x4E6F_7720_6973_2074, u64::MAX] {
+            assert_eq!(des.encrypt_salted(block, 0), des.encrypt(block));
+        }
+        assert_ne!(des.encrypt_salted(0, 1), des.encrypt(0));
+    }
+
+    #[test]
+    fn test_invalid_salt() {
+        for salt in ["", "a", "abc", "a!", "é"] {
+            assert_eq!(crypt(b"foo", salt), Err(Error::InvalidSalt), "{:?}", salt);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::Error;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// DER tag of an `OCTET STRING`.
+const OCTET_STRING: u8 = 0x04;
+
+/// Encodes a DES key as a DER `OCTET STRING`: `04 08` followed by the key bytes.
+pub fn key_to_der(key: [u8; 8]) -> Vec<u8> {
+    let mut der = vec![OCTET_STRING, key.len() as u8];
+    der.extend_from_slice(&key);
+    der
+}
+
+/// Parses a DES key from the DER `OCTET STRING` written by [`key_to_der`].
+/// Anything else, including trailing bytes or a non-minimal length, is rejected.
+pub fn key_from_der(der: &[u8]) -> Result<[u8; 8], Error> {
+    match der {
+        [OCTET_STRING, 8, key @ ..] if key.len() == 8 => Ok(key.try_into().unwrap()),
+        _ => Err(Error::InvalidDer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 8] = [0x13, 0x34, 0x57, 0x79, 0x9B, 0xBC, 0xDF, 0xF1];
+
+    #[test]
+    fn test_round_trip() {
+        let der = key_to_der(KEY);
+        assert_eq!(
+            der,
+            [0x04, 0x08, 0x13, 0x34, 0x57, 0x79, 0x9B, 0xBC, 0xDF, 0xF1]
+        );
+        assert_eq!(key_from_der(&der), Ok(KEY));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let der = key_to_der(KEY);
+        let mut wrong_tag = der.clone();
+        wrong_tag[0] = 0x03;
+        let mut wrong_len = der.clone();
+        wrong_len[1] = 0x07;
+        let mut trailing = der.clone();
+        trailing.push(0);
+        // Long-form length is valid BER but not DER.
+        let long_form = [&[0x04, 0x81, 0x08][..], &KEY].concat();
+
+        for malformed in [
+            &[][..],
+            &der[..9],
+            &wrong_tag,
+            &wrong_len,
+            &trailing,
+            &long_form,
+        ] {
+            assert_eq!(
+                key_from_der(malformed),
+                Err(Error::InvalidDer),
+                "{:02X?}",
+                malformed
+            );
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use core::fmt;
+
+use rand_core::RngCore;
+
+use super::cipher::Des;
+use crate::Error;
+
+/// Ignores the parity bit (lowest bit) of every key byte.
+const EFFECTIVE_KEY_MASK: u64 = 0xFEFE_FEFE_FEFE_FEFE;
+
+/// Keys that produce identical round keys, so encryption equals decryption.
+/// Listed in canonical form, with odd parity on every byte.
+pub const WEAK_KEYS: &[u64] = &[
+    0x0101_0101_0101_0101,
+    0xFEFE_FEFE_FEFE_FEFE,
+    0xE0E0_E0E0_F1F1_F1F1,
+    0x1F1F_1F1F_0E0E_0E0E,
+];
+
+/// Key pairs where encryption under one key is decryption under the other.
+/// Listed in canonical form, with odd parity on every byte.
+pub const SEMI_WEAK_KEY_PAIRS: &[(u64, u64)] = &[
+    (0x011F_011F_010E_010E, 0x1F01_1F01_0E01_0E01),
+    (0x01E0_01E0_01F1_01F1, 0xE001_E001_F101_F101),
+    (0x01FE_01FE_01FE_01FE, 0xFE01_FE01_FE01_FE01),
+    (0x1FE0_1FE0_0EF1_0EF1, 0xE01F_E01F_F10E_F10E),
+    (0x1FFE_1FFE_0EFE_0EFE, 0xFE1F_FE1F_FE0E_FE0E),
+    (0xE0FE_E0FE_F1FE_F1FE, 0xFEE0_FEE0_FEF1_FEF1),
+];
+
+/// Reports whether `key` is one of the four DES weak keys.
+/// Parity bits are ignored, since they do not affect the key schedule.
+pub fn is_weak_key(key: u64) -> bool {
+    WEAK_KEYS
+        .iter()
+        .any(|&weak| weak & EFFECTIVE_KEY_MASK == key & EFFECTIVE_KEY_MASK)
+}
+
+/// Reports whether `key` belongs to one of the six DES semi-weak key pairs.
+/// Parity bits are ignored, since they do not affect the key schedule.
+pub fn is_semi_weak_key(key: u64) -> bool {
+    SEMI_WEAK_KEY_PAIRS.iter().any(|&(k1, k2)| {
+        k1 & EFFECTIVE_KEY_MASK == key & EFFECTIVE_KEY_MASK
+            || k2 & EFFECTIVE_KEY_MASK == key & EFFECTIVE_KEY_MASK
+    })
+}
+
+/// Why [`Des::try_new`] refused a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesKeyError {
+    /// The key is one of the four weak keys, for which encryption equals decryption.
+    WeakKey(u64),
+    /// The key belongs to a semi-weak pair, whose partner decrypts what it encrypts.
+    SemiWeakKey(u64),
+}
+
+impl fmt::Display for DesKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WeakKey(key) => write!(f, "{:016X} is a weak DES key", key),
+            Self::SemiWeakKey(key) => write!(f, "{:016X} is a semi-weak DES key", key),
+        }
+    }
+}
+
+impl From<DesKeyError> for Error {
+    fn from(_: DesKeyError) -> Self {
+        Error::WeakKey
+    }
+}
+
+/// Fixes the lowest bit of every key byte so each byte has odd parity.
+pub fn set_key_parity(key: &mut [u8; 8]) {
+    *key = key.map(with_odd_parity);
+}
+
+/// Reports whether every key byte already has odd parity.
+pub fn check_key_parity(key: &[u8; 8]) -> bool {
+    key.iter().all(|b| b.count_ones() % 2 == 1)
+}
+
+/// Sets the lowest bit of `byte` so the byte has an odd number of ones.
+fn with_odd_parity(byte: u8) -> u8 {
+    let high_bits = byte & 0xFE;
+    if high_bits.count_ones().is_multiple_of(2) {
+        high_bits | 1
+    } else {
+        high_bits
+    }
+}
+
+impl Des {
+    /// Same as [`Des::new`], but refuses weak and semi-weak keys.
+    /// Parity bits are ignored when matching.
+    pub fn try_new(key: u64) -> Result<Self, DesKeyError> {
+        if is_weak_key(key) {
+            return Err(DesKeyError::WeakKey(key));
+        }
+        if is_semi_weak_key(key) {
+            return Err(DesKeyError::SemiWeakKey(key));
+        }
+        Ok(Self::new(key))
+    }
+
+    /// Draws random keys from `rng` until one is neither weak nor semi-weak.
+    /// The returned key has odd parity set on every byte.
+    pub fn generate_strong_key<R: RngCore>(rng: &mut R) -> [u8; 8] {
+        loop {
+            let mut key = [0u8; 8];
+            rng.fill_bytes(&mut key);
+            set_key_parity(&mut key);
+
+            let k = u64::from_be_bytes(key);
+            if !is_weak_key(k) && !is_semi_weak_key(k) {
+                return key;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const BLOCK: u64 = 0x0123_4567_89AB_CDEF;
+
+    #[test]
+    fn test_weak_keys() {
+        for &key in WEAK_KEYS {
+            let cipher = Des::new(key);
+            assert_eq!(cipher.encrypt(cipher.encrypt(BLOCK)), BLOCK, "{:016X}", key);
+            assert!(is_weak_key(key));
+            assert!(
+                is_weak_key(key ^ 0x0101_0101_0101_0101),
+                "parity is ignored"
+            );
+        }
+        assert!(!is_weak_key(0x1334_5779_9BBC_DFF1));
+    }
+
+    #[test]
+    fn test_semi_weak_keys() {
+        for &(k1, k2) in SEMI_WEAK_KEY_PAIRS {
+            let (c1, c2) = (Des::new(k1), Des::new(k2));
+            assert_eq!(c2.encrypt(c1.encrypt(BLOCK)), BLOCK, "{:016X}", k1);
+            assert!(is_semi_weak_key(k1) && is_semi_weak_key(k2));
+        }
+        assert!(!is_semi_weak_key(0x1334_5779_9BBC_DFF1));
+    }
+
+    #[test]
+    fn test_try_new() {
+        for &key in WEAK_KEYS {
+            assert!(matches!(Des::try_new(key), Err(DesKeyError::WeakKey(k)) if k == key));
+        }
+        for &(k1, k2) in SEMI_WEAK_KEY_PAIRS {
+            for key in [k1, k2] {
+                assert!(matches!(Des::try_new(key), Err(DesKeyError::SemiWeakKey(k)) if k == key));
+            }
+        }
+
+        let key = 0x1334_5779_9BBC_DFF1;
+        assert!(Des::try_new(key).unwrap().schedule_matches(&Des::new(key)));
+
+        assert_eq!(
+            DesKeyError::WeakKey(WEAK_KEYS[0]).to_string(),
+            "0101010101010101 is a weak DES key"
+        );
+        assert_eq!(Error::from(DesKeyError::SemiWeakKey(0)), Error::WeakKey);
+    }
+
+    #[test]
+    fn test_public_lists() {
+        assert_eq!(WEAK_KEYS.len(), 4);
+        assert_eq!(SEMI_WEAK_KEY_PAIRS.len(), 6);
+
+        let all_keys = WEAK_KEYS
+            .iter()
+            .copied()
+            .chain(SEMI_WEAK_KEY_PAIRS.iter().flat_map(|&(k1, k2)| [k1, k2]));
+        for key in all_keys {
+            let bytes = key.to_be_bytes();
+            assert!(
+                bytes.iter().all(|&b| with_odd_parity(b) == b),
+                "{:016X}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_odd_parity() {
+        assert_eq!(with_odd_parity(0x00), 0x01);
+        assert_eq!(with_odd_parity(0x01), 0x01);
+        assert_eq!(with_odd_parity(0xFF), 0xFE);
+        assert_eq!(with_odd_parity(0x13), 0x13);
+    }
+
+    #[test]
+    fn test_key_parity() {
+        let mut key = [0u8; 8];
+        assert!(!check_key_parity(&key));
+        set_key_parity(&mut key);
+        assert_eq!(key, [0x01; 8]);
+        assert!(check_key_parity(&key));
+
+        // Only the lowest bit of each byte changes.
+        let mut key = 0x1234_5678_9ABC_DEF0u64.to_be_bytes();
+        assert!(!check_key_parity(&key));
+        set_key_parity(&mut key);
+        assert_eq!(u64::from_be_bytes(key), 0x1334_5779_9BBC_DFF1);
+
+        // A key with correct parity is left unchanged.
+        let mut canonical = 0x0123_4567_89AB_CDEFu64.to_be_bytes();
+        assert!(check_key_parity(&canonical));
+        set_key_parity(&mut canonical);
+        assert_eq!(canonical, 0x0123_4567_89AB_CDEFu64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_generate_strong_key() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let key = Des::generate_strong_key(&mut rng);
+            let k = u64::from_be_bytes(key);
+
+            assert!(!is_weak_key(k) && !is_semi_weak_key(k));
+            assert!(check_key_parity(&key), "{:02X?}", key);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Message authentication codes built directly on DES.
+
+use super::Des;
+use crate::Error;
+use crate::constant_time::ct_eq;
+
+/// CBC-MAC over DES (FIPS 113, ANSI X9.9): the last block of a zero-IV CBC
+/// encryption of the message.
+///
+/// A trailing partial block is padded with zero bytes, so messages that differ
+/// only in trailing zeros share a tag. CBC-MAC is only secure when all messages
+/// under a key have the same length; otherwise prefer [`Cmac`](crate::mac::Cmac).
+pub struct CbcMac {
+    cipher: Des,
+    /// CBC chaining value.
+    state: u64,
+    /// Pending bytes of an incomplete block.
+    buf: [u8; 8],
+    buf_len: usize,
+}
+
+impl CbcMac {
+    /// Creates a CBC-MAC keyed with the 64-bit DES `key`.
+    pub fn new(key: u64) -> Self {
+        Self {
+            cipher: Des::new(key),
+            state: 0,
+            buf: [0u8; 8],
+            buf_len: 0,
+        }
+    }
+
+    /// Feeds more message bytes into the MAC.
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.buf[self.buf_len] = b;
+            self.buf_len += 1;
+            if self.buf_len == 8 {
+                self.absorb();
+            }
+        }
+    }
+
+    /// Zero-pads any partial block and returns the tag.
+    pub fn finalize(mut self) -> [u8; 8] {
+        if self.buf_len > 0 {
+            self.buf[self.buf_len..].fill(0);
+            self.absorb();
+        }
+        self.state.to_be_bytes()
+    }
+
+    /// Computes the tag and compares it with `tag` without an early exit.
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+
+    /// XORs the buffered block into the chaining value and encrypts it.
+    fn absorb(&mut self) {
+        self.state = self
+            .cipher
+            .encrypt(self.state ^ u64::from_be_bytes(self.buf));
+        self.buf_len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac(data: &[u8]) -> [u8; 8] {
+        let mut mac = CbcMac::new(0x0123_4567_89AB_CDEF);
+        mac.update(data);
+        mac.finalize()
+    }
+
+    /// FIPS 113 / ANSI X9.9 example.
+    #[test]
+    fn test_fips_113() {
+        let message = b"7654321 Now is the time for ";
+        assert_eq!(mac(message), 0xF1D3_0F68_4931_2CA4u64.to_be_bytes());
+
+        // Split updates give the same tag.
+        let mut split = CbcMac::new(0x0123_4567_89AB_CDEF);
+        for chunk in message.chunks(3) {
+            split.update(chunk);
+        }
+        assert_eq!(split.finalize(), mac(message));
+    }
+
+    #[test]
+    fn test_single_byte_change() {
+        let message = *b"sixteen byte msg";
+        let tag = mac(&message);
+
+        for i in 0..message.len() {
+            let mut changed = message;
+            changed[i] ^= 0x01;
+            assert_ne!(mac(&changed), tag, "byte {}", i);
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = mac(b"verify me");
+
+        let mut mac = CbcMac::new(0x0123_4567_89AB_CDEF);
+        mac.update(b"verify me");
+        assert_eq!(mac.verify(&tag), Ok(()));
+
+        let mut mac = CbcMac::new(0x0123_4567_89AB_CDEF);
+        mac.update(b"verify me!");
+        assert_eq!(mac.verify(&tag), Err(Error::AuthenticationFailed));
+    }
+}
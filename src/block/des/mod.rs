@@ -1,5 +1,28 @@
+pub mod aont;
+mod bitslice;
 mod cipher;
+mod crypt;
+mod der;
+#[cfg(test)]
+mod kat;
+mod keys;
+pub mod mac;
+pub mod modes;
+mod nonlinearity;
 mod permutation_tables;
+mod process;
 mod s_boxes;
+pub mod siv;
+// The fused tables index memory with secret data, so constant-time builds skip them.
+#[cfg(any(not(feature = "constant-time"), test))]
+mod sp_boxes;
 
-pub use cipher::Des;
+pub use cipher::{Des, encrypt_with_keys, feistel_round};
+pub use crypt::crypt;
+pub use der::{key_from_der, key_to_der};
+pub use keys::{
+    DesKeyError, SEMI_WEAK_KEY_PAIRS, WEAK_KEYS, check_key_parity, is_semi_weak_key, is_weak_key,
+    set_key_parity,
+};
+pub use nonlinearity::{sbox_nonlinearity, standard_sbox_nonlinearities};
+pub use process::process_into;
@@ -0,0 +1,162 @@
+//! The generic [modes of operation](crate::modes) specialized to DES.
+
+use super::Des;
+use crate::modes;
+
+/// DES in CBC mode, taking an 8-byte IV and block-aligned input.
+pub type Cbc = modes::Cbc<Des>;
+
+/// DES in CTR mode, taking an 8-byte counter block that increments big-endian.
+pub type Ctr = modes::Ctr<Des>;
+
+/// DES in full 64-bit CFB mode, taking an 8-byte IV and input of any length.
+pub type Cfb = modes::Cfb<Des>;
+
+/// DES in 8-bit CFB mode, taking an 8-byte IV and input of any length.
+pub type Cfb8 = modes::Cfb8<Des>;
+
+/// DES in OFB mode, taking an 8-byte IV and input of any length.
+pub type Ofb = modes::Ofb<Des>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    /// FIPS 81 Appendix C, the classic DES-CBC example.
+    #[test]
+    fn test_cbc_fips_81() {
+        let key = 0x0123_4567_89AB_CDEF;
+        let iv = 0x1234_5678_90AB_CDEFu64.to_be_bytes();
+
+        let mut buf = *b"Now is the time for all ";
+        Cbc::new(Des::new(key), &iv)
+            .unwrap()
+            .encrypt(&mut buf)
+            .unwrap();
+
+        let expected: Vec<u8> = [
+            0xE5C7_CDDE_872B_F27Cu64,
+            0x43E9_3400_8C38_9C0F,
+            0x6837_8849_9A7C_05F6,
+        ]
+        .iter()
+        .flat_map(|block| block.to_be_bytes())
+        .collect();
+        assert_eq!(buf[..], expected[..]);
+
+        Cbc::new(Des::new(key), &iv)
+            .unwrap()
+            .decrypt(&mut buf)
+            .unwrap();
+        assert_eq!(&buf, b"Now is the time for all ");
+    }
+
+    #[test]
+    fn test_cbc_rejects_bad_input() {
+        assert!(matches!(
+            Cbc::new(Des::new(1), &[0u8; 16]),
+            Err(Error::InvalidLength)
+        ));
+
+        let mut cbc = Cbc::new(Des::new(1), &[0u8; 8]).unwrap();
+        assert_eq!(cbc.encrypt(&mut [0u8; 12]), Err(Error::UnalignedInput));
+    }
+
+    #[test]
+    fn test_ctr_symmetry() {
+        let nonce = 0x0011_2233_4455_6677u64;
+        let message = b"any length, no padding needed";
+
+        let mut ctr = Ctr::new(Des::new(0x1334_5779_9BBC_DFF1), &nonce.to_be_bytes()).unwrap();
+        let mut buf = message.to_vec();
+        ctr.apply_keystream(&mut buf);
+        assert_ne!(&buf[..], message);
+
+        // 29 bytes take 4 counter blocks, the last one only partly used.
+        assert_eq!(ctr.counter(), nonce as u128 + 4);
+
+        Ctr::new(Des::new(0x1334_5779_9BBC_DFF1), &nonce.to_be_bytes())
+            .unwrap()
+            .apply_keystream(&mut buf);
+        assert_eq!(&buf[..], message);
+    }
+
+    #[test]
+    fn test_ctr_wraparound() {
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+        let mut ctr = Ctr::new(Des::new(0x1334_5779_9BBC_DFF1), &[0xFF; 8]).unwrap();
+
+        let mut keystream = [0u8; 16];
+        ctr.apply_keystream(&mut keystream);
+
+        // The 64-bit counter rolls over from all ones to zero.
+        assert_eq!(keystream[..8], des.encrypt(u64::MAX).to_be_bytes());
+        assert_eq!(keystream[8..], des.encrypt(0).to_be_bytes());
+        assert_eq!(ctr.counter(), 1);
+    }
+
+    #[test]
+    fn test_cfb_round_trip() {
+        let iv = [0x42u8; 8];
+        let message = b"CFB turns DES into a self-synchronizing stream";
+
+        let mut buf = message.to_vec();
+        Cfb::new(Des::new(0x1334_5779_9BBC_DFF1), &iv)
+            .unwrap()
+            .encrypt(&mut buf);
+        assert_eq!(buf.len(), message.len());
+
+        Cfb::new(Des::new(0x1334_5779_9BBC_DFF1), &iv)
+            .unwrap()
+            .decrypt(&mut buf);
+        assert_eq!(&buf[..], message);
+    }
+
+    #[test]
+    fn test_cfb_self_synchronizes() {
+        let iv = [0x42u8; 8];
+        let message = [0x55u8; 32];
+        let cfb = || Cfb::new(Des::new(0x1334_5779_9BBC_DFF1), &iv).unwrap();
+
+        let mut buf = message;
+        cfb().encrypt(&mut buf);
+        buf[3] ^= 0x80;
+        cfb().decrypt(&mut buf);
+
+        // The flipped bit comes through in its own byte, garbles the next block,
+        // and decryption is back on track from the block after that.
+        assert_eq!(buf[3], message[3] ^ 0x80);
+        assert_ne!(buf[8..16], message[8..16]);
+        assert_eq!(buf[16..], message[16..]);
+    }
+
+    #[test]
+    fn test_ofb_round_trip() {
+        let iv = [0x42u8; 8];
+        let message = b"OFB encrypts and decrypts with one call";
+        let ofb = || Ofb::new(Des::new(0x1334_5779_9BBC_DFF1), &iv).unwrap();
+
+        let mut buf = message.to_vec();
+        ofb().apply_keystream(&mut buf);
+        assert_ne!(&buf[..], message);
+        ofb().apply_keystream(&mut buf);
+        assert_eq!(&buf[..], message);
+    }
+
+    #[test]
+    fn test_ofb_keystream_ignores_plaintext() {
+        let iv = [0x42u8; 8];
+        let ofb = || Ofb::new(Des::new(0x1334_5779_9BBC_DFF1), &iv).unwrap();
+
+        let mut zeros = [0u8; 24];
+        ofb().apply_keystream(&mut zeros);
+
+        let message = *b"any twenty-four bytes...";
+        let mut buf = message;
+        ofb().apply_keystream(&mut buf);
+
+        let keystream: Vec<u8> = buf.iter().zip(&message).map(|(c, p)| c ^ p).collect();
+        assert_eq!(keystream, zeros);
+    }
+}
@@ -84,20 +84,71 @@ pub(super) const P: [u8; 32] = [
 mod tests {
     use super::*;
 
+    /// Counts how often each input position `1..=size` appears in `table`,
+    /// failing on entries outside that range. Index 0 of the result is unused.
+    fn occurrences(table: &[u8], size: usize) -> Vec<usize> {
+        let mut counts = vec![0; size + 1];
+        for &n in table {
+            assert!(
+                (1..=size).contains(&(n as usize)),
+                "entry {} out of range",
+                n
+            );
+            counts[n as usize] += 1;
+        }
+        counts
+    }
+
+    /// Asserts that `table` lists every position `1..=size` exactly once.
+    fn assert_permutation(table: &[u8], size: usize) {
+        assert_eq!(table.len(), size);
+        assert!(occurrences(table, size)[1..].iter().all(|&c| c == 1));
+    }
+
     #[test]
     fn test_permutation_tables() {
-        // TODO: test other tables
+        assert_permutation(&INITIAL_PERMUTATION, 64);
+        assert_permutation(&FINAL_PERMUTATION, 64);
         for (i, &n) in INITIAL_PERMUTATION.iter().enumerate() {
             assert_eq!((i + 1) as u8, FINAL_PERMUTATION[(n - 1) as usize]);
         }
 
-        // Weak guard, but still better than nothing.
+        assert_permutation(&P, 32);
+    }
+
+    #[test]
+    fn test_expansion_table() {
+        assert_eq!(E.len(), 48);
+        let counts = occurrences(&E, 32);
+
+        // The two edge bits of every 4-bit group are shared with the
+        // neighbouring 6-bit chunk, so they appear twice.
+        for (n, &count) in counts.iter().enumerate().skip(1) {
+            let expected = if matches!(n % 4, 0 | 1) { 2 } else { 1 };
+            assert_eq!(count, expected, "bit {}", n);
+        }
+    }
+
+    #[test]
+    fn test_key_schedule_tables() {
+        // PC-1 keeps every bit but the parity bit of each byte.
+        assert_eq!(PC_1.len(), 56);
+        let counts = occurrences(&PC_1, 64);
+        for (n, &count) in counts.iter().enumerate().skip(1) {
+            let expected = if n % 8 == 0 { 0 } else { 1 };
+            assert_eq!(count, expected, "bit {}", n);
+        }
+
+        // PC-2 picks 48 distinct bits out of 56, dropping these eight.
+        assert_eq!(PC_2.len(), 48);
+        let counts = occurrences(&PC_2, 56);
+        assert!(counts.iter().all(|&c| c <= 1));
+        let dropped: Vec<usize> = (1..=56).filter(|&n| counts[n] == 0).collect();
+        assert_eq!(dropped, [9, 18, 22, 25, 35, 38, 43, 54]);
+
+        // The first 24 bits come from the left half C and the rest from D.
         for (i, &n) in PC_2.iter().enumerate() {
-            if i < 24 {
-                assert!(n <= 28)
-            } else {
-                assert!(n > 28)
-            };
+            assert_eq!(n <= 28, i < 24, "PC_2[{}] = {}", i, n);
         }
     }
 }
@@ -0,0 +1,202 @@
+use super::Des;
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::modes::{Cbc, Cfb, Ctr, Direction, Mode, Ofb};
+use crate::padding::pkcs7_unpadded_len;
+
+/// Runs `input` through `cipher` in `mode`, writing the result into `output`
+/// without allocating, and returns the number of bytes written.
+///
+/// ECB and CBC add PKCS#7 padding when encrypting, so `output` needs room for one
+/// more block than the aligned input, and strip it when decrypting. The stream modes
+/// write exactly `input.len()` bytes. `iv` must be 8 bytes and is ignored by ECB.
+pub fn process_into(
+    cipher: &Des,
+    mode: Mode,
+    iv: &[u8],
+    direction: Direction,
+    input: &[u8],
+    output: &mut [u8],
+) -> Result<usize, Error> {
+    let written = match (mode.is_padded(), direction) {
+        (true, Direction::Encrypt) => Des::block_count(input.len(), mode) * Des::BLOCK_SIZE,
+        _ => input.len(),
+    };
+    if output.len() < written {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let buf = &mut output[..written];
+    buf[..input.len()].copy_from_slice(input);
+    if mode.is_padded() && direction == Direction::Encrypt {
+        let pad_len = written - input.len();
+        buf[input.len()..].fill(pad_len as u8);
+    }
+
+    match (mode, direction) {
+        (Mode::Ecb, _) => {
+            if !buf.len().is_multiple_of(Des::BLOCK_SIZE) {
+                return Err(Error::UnalignedInput);
+            }
+            for block in buf.chunks_exact_mut(Des::BLOCK_SIZE) {
+                match direction {
+                    Direction::Encrypt => cipher.encrypt_block(block),
+                    Direction::Decrypt => cipher.decrypt_block(block),
+                }
+            }
+        }
+        (Mode::Cbc, Direction::Encrypt) => Cbc::new(cipher, iv)?.encrypt(buf)?,
+        (Mode::Cbc, Direction::Decrypt) => Cbc::new(cipher, iv)?.decrypt(buf)?,
+        (Mode::Cfb, Direction::Encrypt) => Cfb::new(cipher, iv)?.encrypt(buf),
+        (Mode::Cfb, Direction::Decrypt) => Cfb::new(cipher, iv)?.decrypt(buf),
+        (Mode::Ofb, _) => Ofb::new(cipher, iv)?.apply_keystream(buf),
+        (Mode::Ctr, _) => Ctr::new(cipher, iv)?.apply_keystream(buf),
+    }
+
+    match (mode.is_padded(), direction) {
+        (true, Direction::Decrypt) => pkcs7_unpadded_len(buf, Des::BLOCK_SIZE),
+        _ => Ok(written),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+    const IV: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF];
+    const MESSAGE: &[u8] = b"no intermediate Vec";
+
+    /// Encrypts with `process_into`, checks it against the mode's own type, then decrypts.
+    fn round_trip(mode: Mode, expected: &[u8]) {
+        let des = Des::new(KEY);
+        let mut ciphertext = [0u8; 32];
+        let len = process_into(
+            &des,
+            mode,
+            &IV,
+            Direction::Encrypt,
+            MESSAGE,
+            &mut ciphertext,
+        )
+        .unwrap();
+        assert_eq!(&ciphertext[..len], expected, "{:?}", mode);
+
+        let mut plaintext = [0u8; 32];
+        let len = process_into(
+            &des,
+            mode,
+            &IV,
+            Direction::Decrypt,
+            &ciphertext[..len],
+            &mut plaintext,
+        )
+        .unwrap();
+        assert_eq!(&plaintext[..len], MESSAGE, "{:?}", mode);
+    }
+
+    #[test]
+    fn test_ecb() {
+        let des = Des::new(KEY);
+        let mut expected = MESSAGE.to_vec();
+        crate::padding::pkcs7_pad(&mut expected, 8);
+        for block in expected.chunks_exact_mut(8) {
+            des.encrypt_block(block);
+        }
+        round_trip(Mode::Ecb, &expected);
+    }
+
+    #[test]
+    fn test_cbc() {
+        let expected = Cbc::new(Des::new(KEY), &IV)
+            .unwrap()
+            .encrypt_padded(MESSAGE);
+        round_trip(Mode::Cbc, &expected);
+    }
+
+    #[test]
+    fn test_cfb() {
+        let mut expected = MESSAGE.to_vec();
+        Cfb::new(Des::new(KEY), &IV).unwrap().encrypt(&mut expected);
+        round_trip(Mode::Cfb, &expected);
+    }
+
+    #[test]
+    fn test_ofb() {
+        let mut expected = MESSAGE.to_vec();
+        Ofb::new(Des::new(KEY), &IV)
+            .unwrap()
+            .apply_keystream(&mut expected);
+        round_trip(Mode::Ofb, &expected);
+    }
+
+    #[test]
+    fn test_ctr() {
+        let mut expected = MESSAGE.to_vec();
+        Ctr::new(Des::new(KEY), &IV)
+            .unwrap()
+            .apply_keystream(&mut expected);
+        round_trip(Mode::Ctr, &expected);
+    }
+
+    #[test]
+    fn test_output_too_small() {
+        let des = Des::new(KEY);
+
+        // 19 bytes pad to 24.
+        let mut output = [0u8; 23];
+        assert_eq!(
+            process_into(
+                &des,
+                Mode::Cbc,
+                &IV,
+                Direction::Encrypt,
+                MESSAGE,
+                &mut output
+            ),
+            Err(Error::BufferTooSmall)
+        );
+
+        let mut output = [0u8; 18];
+        assert_eq!(
+            process_into(
+                &des,
+                Mode::Ctr,
+                &IV,
+                Direction::Encrypt,
+                MESSAGE,
+                &mut output
+            ),
+            Err(Error::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let des = Des::new(KEY);
+        let mut output = [0u8; 32];
+
+        assert_eq!(
+            process_into(
+                &des,
+                Mode::Ecb,
+                &IV,
+                Direction::Decrypt,
+                MESSAGE,
+                &mut output
+            ),
+            Err(Error::UnalignedInput)
+        );
+        assert_eq!(
+            process_into(
+                &des,
+                Mode::Ofb,
+                &IV[..4],
+                Direction::Encrypt,
+                MESSAGE,
+                &mut output
+            ),
+            Err(Error::InvalidLength)
+        );
+    }
+}
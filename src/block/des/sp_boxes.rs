@@ -0,0 +1,65 @@
+use super::cipher::s_box_index;
+use super::permutation_tables::P;
+use super::s_boxes::S;
+
+/// Combined S-box and P permutation tables, built at compile time.
+///
+/// `SP[i][chunk]` is the output of S-box `i` for the raw 6-bit `chunk`, placed
+/// at its nibble of the 32-bit S-box output and already permuted by `P`. The
+/// eight entries of a round touch disjoint bits, so ORing them gives f directly.
+pub(super) const SP: [[u32; 64]; 8] = build_sp();
+
+const fn build_sp() -> [[u32; 64]; 8] {
+    let mut table = [[0u32; 64]; 8];
+
+    let mut i = 0;
+    while i < 8 {
+        let mut chunk = 0;
+        while chunk < 64 {
+            let nibble = S[i][s_box_index(chunk as u64)] as u32;
+            table[i][chunk] = permute_p(nibble << (28 - 4 * i));
+            chunk += 1;
+        }
+        i += 1;
+    }
+
+    table
+}
+
+/// Applies `P` to a 32-bit value, like `permutate(x, &P, 32)` but usable in a const.
+const fn permute_p(x: u32) -> u32 {
+    let mut result = 0;
+
+    let mut i = 0;
+    while i < P.len() {
+        result = (result << 1) | ((x >> (32 - P[i] as u32)) & 1);
+        i += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sp_entries_are_disjoint() {
+        // Each S-box feeds four output bits, and P spreads them over distinct positions.
+        let mut seen = 0u32;
+        for table in &SP {
+            let bits = table.iter().fold(0, |acc, &entry| acc | entry);
+            assert_eq!(bits.count_ones(), 4);
+            assert_eq!(seen & bits, 0);
+            seen |= bits;
+        }
+        assert_eq!(seen, u32::MAX);
+    }
+
+    #[test]
+    fn test_permute_p() {
+        // Bit 16 of the input moves to the first output position.
+        assert_eq!(permute_p(1 << (32 - 16)), 1 << 31);
+        assert_eq!(permute_p(u32::MAX), u32::MAX);
+    }
+}
@@ -1,2 +1,151 @@
 pub mod aes;
 pub mod des;
+mod desx;
+mod triple_des;
+mod whitened;
+
+pub use desx::DesX;
+pub use triple_des::TripleDes;
+pub use whitened::Whitened;
+
+#[cfg(feature = "rayon")]
+use crate::padding::pkcs7_pad;
+#[cfg(feature = "rayon")]
+use alloc::vec::Vec;
+
+/// A cipher that transforms fixed-size blocks of bytes.
+/// Modes of operation are written once against this trait.
+///
+/// The block methods are low-level primitives and panic when `block` is not
+/// exactly `BLOCK_SIZE` bytes long. The modes validate lengths up front and
+/// return an [`Error`](crate::Error) instead.
+pub trait BlockCipher {
+    /// Size of a single block in bytes.
+    const BLOCK_SIZE: usize;
+
+    /// Encrypts `block` in place. Its length must equal `BLOCK_SIZE`.
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    /// Decrypts `block` in place. Its length must equal `BLOCK_SIZE`.
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+impl<C: BlockCipher> BlockCipher for &C {
+    const BLOCK_SIZE: usize = C::BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        (**self).encrypt_block(block);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        (**self).decrypt_block(block);
+    }
+}
+
+/// Pads `data` with PKCS#7 and encrypts every block independently (ECB mode),
+/// spreading the blocks over the rayon thread pool.
+///
+/// Only modes whose blocks do not depend on each other can be split like this:
+/// ECB, and CTR when every block derives its own counter. The chained modes
+/// must run block by block.
+#[cfg(feature = "rayon")]
+pub(crate) fn encrypt_ecb_par<C: BlockCipher + Sync>(cipher: &C, data: &[u8]) -> Vec<u8> {
+    use rayon::prelude::*;
+
+    let mut buf = data.to_vec();
+    pkcs7_pad(&mut buf, C::BLOCK_SIZE);
+    buf.par_chunks_mut(C::BLOCK_SIZE)
+        .for_each(|block| cipher.encrypt_block(block));
+    buf
+}
+
+/// XORs `block` with the leading bytes of `mask`.
+pub(crate) fn xor_in_place(block: &mut [u8], mask: &[u8]) {
+    for (b, m) in block.iter_mut().zip(mask) {
+        *b ^= m;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::Aes;
+    use des::Des;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    /// Encrypts and decrypts through nothing but the trait.
+    fn round_trip<C: BlockCipher>(cipher: &C, block: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut encrypted = block.to_vec();
+        cipher.encrypt_block(&mut encrypted);
+        let mut decrypted = encrypted.clone();
+        cipher.decrypt_block(&mut decrypted);
+        (encrypted, decrypted)
+    }
+
+    #[test]
+    fn test_trait_matches_concrete_api() {
+        let mut rng = StdRng::seed_from_u64(509);
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+        let aes = Aes::new(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]).unwrap();
+
+        for _ in 0..100 {
+            let block = rng.next_u64();
+            let (encrypted, decrypted) = round_trip(&des, &block.to_be_bytes());
+            assert_eq!(encrypted, des.encrypt(block).to_be_bytes());
+            assert_eq!(decrypted, block.to_be_bytes());
+
+            let mut block = [0u8; 16];
+            rng.fill_bytes(&mut block);
+            let (encrypted, decrypted) = round_trip(&aes, &block);
+            assert_eq!(encrypted, aes.encrypt(block));
+            assert_eq!(decrypted, block);
+        }
+
+        // References go through the blanket impl unchanged.
+        assert_eq!(round_trip(&&des, &[7u8; 8]), round_trip(&des, &[7u8; 8]));
+    }
+
+    /// Returns `len` random bytes with a fixed seed.
+    #[cfg(feature = "rayon")]
+    fn random_data(len: usize) -> Vec<u8> {
+        let mut rng = StdRng::seed_from_u64(540);
+        let mut data = vec![0u8; len];
+        rng.fill_bytes(&mut data);
+        data
+    }
+
+    /// Encrypts `data` in ECB mode block by block, as the reference for the parallel helper.
+    #[cfg(feature = "rayon")]
+    fn encrypt_ecb_serial<C: BlockCipher>(cipher: &C, data: &[u8]) -> Vec<u8> {
+        let mut expected = data.to_vec();
+        crate::padding::pkcs7_pad(&mut expected, C::BLOCK_SIZE);
+        for block in expected.chunks_exact_mut(C::BLOCK_SIZE) {
+            cipher.encrypt_block(block);
+        }
+        expected
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_ecb_par_matches_serial() {
+        let data = random_data(64 * 1024 + 5);
+
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+        assert_eq!(des.encrypt_ecb_par(&data), des.encrypt_ecb(&data));
+
+        let aes = Aes::new(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]).unwrap();
+        assert_eq!(aes.encrypt_ecb_par(&data), encrypt_ecb_serial(&aes, &data));
+    }
+
+    /// A few megabytes through AES only: DES with the `constant-time` S-boxes
+    /// needs about a minute per megabyte in debug builds.
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_ecb_par_matches_serial_large() {
+        let data = random_data(2 * 1024 * 1024 + 5);
+
+        let aes = Aes::new(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]).unwrap();
+        assert_eq!(aes.encrypt_ecb_par(&data), encrypt_ecb_serial(&aes, &data));
+    }
+}
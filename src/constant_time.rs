@@ -0,0 +1,91 @@
+//! Branch-free helpers for comparing and moving secret data.
+//!
+//! Conditions are turned into all-ones or all-zeros masks, so the work done does
+//! not depend on the secret values. Only slice lengths are treated as public.
+
+use core::hint::black_box;
+
+/// Returns `0xFF` when `cond` is true and `0x00` otherwise.
+fn mask(cond: bool) -> u8 {
+    black_box(cond as u8).wrapping_neg()
+}
+
+/// Compares two slices, looking at every byte regardless of where they differ.
+/// Slices of different lengths are unequal.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    black_box(diff) == 0
+}
+
+/// Returns `a` when `cond` is true and `b` otherwise, without branching on `cond`.
+pub fn ct_select(cond: bool, a: u8, b: u8) -> u8 {
+    let mask = mask(cond);
+    (a & mask) | (b & !mask)
+}
+
+/// Copies `src` into `dst` when `cond` is true and leaves `dst` as is otherwise.
+/// Every byte of `dst` is written either way.
+///
+/// # Panics
+///
+/// Panics if `dst` and `src` have different lengths.
+pub fn ct_copy(cond: bool, dst: &mut [u8], src: &[u8]) {
+    assert_eq!(dst.len(), src.len(), "slices must have the same length");
+
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d = ct_select(cond, s, *d);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(&[], &[]));
+        assert!(ct_eq(&[7], &[7]));
+        assert!(ct_eq(b"same bytes", b"same bytes"));
+
+        assert!(!ct_eq(&[7], &[6]));
+        assert!(!ct_eq(b"same bytes", b"same byteS"));
+        assert!(!ct_eq(b"same bytes", b"Same bytes"));
+        assert!(!ct_eq(b"prefix", b"prefix and more"));
+        assert!(!ct_eq(&[], &[0]));
+    }
+
+    #[test]
+    fn test_ct_select() {
+        for (a, b) in [(0x00, 0xFF), (0xFF, 0x00), (0x5A, 0xA5), (42, 42)] {
+            assert_eq!(ct_select(true, a, b), a);
+            assert_eq!(ct_select(false, a, b), b);
+        }
+    }
+
+    #[test]
+    fn test_ct_copy() {
+        let src = [1u8, 2, 3, 4, 5];
+
+        let mut dst = [9u8; 5];
+        ct_copy(true, &mut dst, &src);
+        assert_eq!(dst, src);
+
+        let mut dst = [9u8; 5];
+        ct_copy(false, &mut dst, &src);
+        assert_eq!(dst, [9u8; 5]);
+
+        let mut empty: [u8; 0] = [];
+        ct_copy(true, &mut empty, &[]);
+        ct_copy(false, &mut empty, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_ct_copy_length_mismatch() {
+        ct_copy(false, &mut [0u8; 3], &[0u8; 4]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Hex encoding for printing ciphertext and parsing test vectors.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Error;
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encodes `bytes` as lowercase hex, two characters per byte.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        hex.push(DIGITS[(b >> 4) as usize] as char);
+        hex.push(DIGITS[(b & 0x0F) as usize] as char);
+    }
+    hex
+}
+
+/// Decodes a hex string in either case.
+///
+/// Fails with [`Error::InvalidLength`] for an odd number of characters and
+/// [`Error::InvalidHex`] for anything that is not a hex digit.
+pub fn from_hex(s: &str) -> Result<Vec<u8>, Error> {
+    if !s.len().is_multiple_of(2) {
+        return Err(Error::InvalidLength);
+    }
+
+    s.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| Ok(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
+
+fn digit(c: u8) -> Result<u8, Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(Error::InvalidHex),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let hex = to_hex(&bytes);
+        assert_eq!(hex.len(), 512);
+        assert!(hex.starts_with("000102"));
+        assert!(hex.ends_with("fdfeff"));
+        assert_eq!(from_hex(&hex).unwrap(), bytes);
+
+        assert_eq!(to_hex(&[]), "");
+        assert_eq!(from_hex("").unwrap(), Vec::<u8>::new());
+        assert_eq!(from_hex("DeadBEEF").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn test_odd_length() {
+        assert_eq!(from_hex("abc"), Err(Error::InvalidLength));
+        assert_eq!(from_hex("0"), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    fn test_invalid_characters() {
+        for s in ["0g", "zz", "12 4", "+1", "0x12", "é"] {
+            assert_eq!(from_hex(s), Err(Error::InvalidHex), "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use core::array::TryFromSliceError;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// Errors returned by the fallible parts of the crate.
+///
+/// Marked `#[non_exhaustive]` so that variants which only exist with some
+/// features, such as [`Error::Io`], do not break exhaustive matches downstream.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Input length is not a multiple of the cipher block size.
+    UnalignedInput,
+    /// Padding bytes are missing or malformed.
+    InvalidPadding,
+    /// Input does not have the length the operation expects.
+    InvalidLength,
+    /// Input is not a valid hexadecimal string.
+    InvalidHex,
+    /// Input is not the DER structure the operation expects.
+    InvalidDer,
+    /// A message header names a cipher or mode that is not recognized.
+    UnknownHeader,
+    /// A value does not fit into the number of bits the operation expects.
+    OutOfRange,
+    /// A MAC tag does not match the message.
+    AuthenticationFailed,
+    /// The output buffer cannot hold the result.
+    BufferTooSmall,
+    /// An IV was reused with the same first plaintext block.
+    IvReuse,
+    /// A key does not have a length the cipher accepts.
+    InvalidKeyLength,
+    /// A key is weak or semi-weak and was refused.
+    WeakKey,
+    /// A crypt(3) salt is not two characters from `./0-9A-Za-z`.
+    InvalidSalt,
+    /// Reading or writing the underlying stream failed. The I/O error is kept as
+    /// the [`source`](core::error::Error::source).
+    #[cfg(feature = "std")]
+    Io(Arc<io::Error>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnalignedInput => write!(f, "input length is not a multiple of the block size"),
+            Self::InvalidPadding => write!(f, "padding is malformed"),
+            Self::InvalidLength => write!(f, "input has an invalid length"),
+            Self::InvalidHex => write!(f, "input is not valid hexadecimal"),
+            Self::InvalidDer => write!(f, "input is not the expected DER structure"),
+            Self::UnknownHeader => write!(f, "header names an unknown cipher or mode"),
+            Self::OutOfRange => write!(f, "value does not fit into the expected number of bits"),
+            Self::AuthenticationFailed => write!(f, "message authentication failed"),
+            Self::BufferTooSmall => write!(f, "output buffer is too small"),
+            Self::IvReuse => write!(f, "IV was reused with the same first block"),
+            Self::InvalidKeyLength => write!(f, "key has an invalid length"),
+            Self::WeakKey => write!(f, "key is weak or semi-weak"),
+            Self::InvalidSalt => write!(f, "salt is not two characters from ./0-9A-Za-z"),
+            #[cfg(feature = "std")]
+            Self::Io(e) => write!(f, "I/O operation failed: {}", e.kind()),
+        }
+    }
+}
+
+/// I/O errors are equal when their kinds are, as `io::Error` itself has no equality.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(feature = "std")]
+            (Self::Io(a), Self::Io(b)) => a.kind() == b.kind(),
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Error {}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(Arc::new(e))
+    }
+}
+
+/// A slice that does not fit an array has the wrong length.
+impl From<TryFromSliceError> for Error {
+    fn from(_: TryFromSliceError) -> Self {
+        Self::InvalidLength
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_io_conversion() {
+        use core::error::Error as _;
+
+        let err = Error::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert_eq!(
+            err,
+            Error::from(io::Error::from(io::ErrorKind::UnexpectedEof))
+        );
+        assert_ne!(err, Error::from(io::Error::from(io::ErrorKind::BrokenPipe)));
+        assert_ne!(err, Error::InvalidLength);
+
+        let err = Error::from(io::Error::other("disk on fire"));
+        assert!(matches!(&err, Error::Io(e) if e.kind() == io::ErrorKind::Other));
+        let source = err.source().expect("I/O error is kept as the source");
+        assert_eq!(source.to_string(), "disk on fire");
+        assert!(Error::InvalidLength.source().is_none());
+    }
+
+    #[test]
+    fn test_slice_conversion() {
+        fn to_array(bytes: &[u8]) -> Result<[u8; 8], Error> {
+            Ok(bytes.try_into()?)
+        }
+        assert_eq!(to_array(&[0u8; 8]), Ok([0u8; 8]));
+        assert_eq!(to_array(&[0u8; 7]), Err(Error::InvalidLength));
+    }
+}
@@ -1,2 +1,19 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod aead;
 pub mod block;
+pub mod constant_time;
+pub mod encoding;
+mod error;
+pub mod mac;
+pub mod modes;
+mod oneshot;
+pub mod padding;
+mod seal;
 pub mod stream;
+
+pub use error::Error;
+pub use oneshot::{Algorithm, decrypt, encrypt};
+pub use seal::{Scheme, Sealable, open, open_with_aad, seal, seal_with_aad};
@@ -0,0 +1,200 @@
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::constant_time::ct_eq;
+use alloc::vec::Vec;
+
+/// Cipher-based MAC (CMAC, NIST SP 800-38B).
+///
+/// CBC-MAC with the last block masked by one of two subkeys derived from the
+/// cipher, which makes it secure for messages of any length.
+pub struct Cmac<C: BlockCipher> {
+    cipher: C,
+    /// Subkey for a complete final block.
+    k1: [u8; 16],
+    /// Subkey for a padded final block.
+    k2: [u8; 16],
+    /// CBC chaining value.
+    state: [u8; 16],
+    /// Pending bytes. The last block is held back until `finalize`.
+    buf: [u8; 16],
+    buf_len: usize,
+}
+
+impl<C: BlockCipher> Cmac<C> {
+    /// Creates a `Cmac` keyed by `cipher`, deriving both subkeys from it.
+    ///
+    /// # Panics
+    /// If the cipher's block is not 64 or 128 bits, the sizes CMAC is defined for.
+    pub fn new(cipher: C) -> Self {
+        assert!(
+            C::BLOCK_SIZE == 8 || C::BLOCK_SIZE == 16,
+            "CMAC is defined for 64 and 128-bit blocks"
+        );
+
+        let mut l = [0u8; 16];
+        cipher.encrypt_block(&mut l[..C::BLOCK_SIZE]);
+        let k1 = double::<C>(&l);
+        let k2 = double::<C>(&k1);
+
+        Self {
+            cipher,
+            k1,
+            k2,
+            state: [0u8; 16],
+            buf: [0u8; 16],
+            buf_len: 0,
+        }
+    }
+
+    /// Feeds more message bytes into the MAC.
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            if self.buf_len == C::BLOCK_SIZE {
+                let block = self.buf;
+                self.absorb(&block);
+                self.buf_len = 0;
+            }
+            self.buf[self.buf_len] = b;
+            self.buf_len += 1;
+        }
+    }
+
+    /// Returns the tag, which is one block long.
+    pub fn finalize(mut self) -> Vec<u8> {
+        let mut last = [0u8; 16];
+        let subkey = if self.buf_len == C::BLOCK_SIZE {
+            last = self.buf;
+            self.k1
+        } else {
+            last[..self.buf_len].copy_from_slice(&self.buf[..self.buf_len]);
+            last[self.buf_len] = 0x80;
+            self.k2
+        };
+
+        for (b, k) in last.iter_mut().zip(subkey) {
+            *b ^= k;
+        }
+        self.absorb(&last);
+
+        self.state[..C::BLOCK_SIZE].to_vec()
+    }
+
+    /// Computes the tag and compares it with `tag` without an early exit.
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+
+    /// XORs a block into the chaining value and encrypts it.
+    fn absorb(&mut self, block: &[u8; 16]) {
+        for (s, b) in self.state.iter_mut().zip(block) {
+            *s ^= b;
+        }
+        self.cipher.encrypt_block(&mut self.state[..C::BLOCK_SIZE]);
+    }
+}
+
+/// Multiplies a block by x in GF(2^n): a left shift, reduced by the block size's constant.
+fn double<C: BlockCipher>(block: &[u8; 16]) -> [u8; 16] {
+    let n = C::BLOCK_SIZE;
+    let r_b = if n == 16 { 0x87 } else { 0x1b };
+
+    let mut result = [0u8; 16];
+    for i in 0..n {
+        let next_bit = if i + 1 < n { block[i + 1] >> 7 } else { 0 };
+        result[i] = (block[i] << 1) | next_bit;
+    }
+    if block[0] & 0x80 != 0 {
+        result[n - 1] ^= r_b;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::aes::Aes;
+    use crate::block::des::Des;
+
+    fn mac<C: BlockCipher>(cipher: C, data: &[u8]) -> Vec<u8> {
+        let mut cmac = Cmac::new(cipher);
+        cmac.update(data);
+        cmac.finalize()
+    }
+
+    /// NIST SP 800-38B AES-128 examples.
+    #[test]
+    fn test_aes_cmac() {
+        let key = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+        #[rustfmt::skip]
+        let message: [u8; 64] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+            0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+            0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11, 0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+            0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17, 0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10,
+        ];
+        #[rustfmt::skip]
+        let cases: [(usize, [u8; 16]); 4] = [
+            (0, [0xbb, 0x1d, 0x69, 0x29, 0xe9, 0x59, 0x37, 0x28, 0x7f, 0xa3, 0x7d, 0x12, 0x9b, 0x75, 0x67, 0x46]),
+            (16, [0x07, 0x0a, 0x16, 0xb4, 0x6b, 0x4d, 0x41, 0x44, 0xf7, 0x9b, 0xdd, 0x9d, 0xd0, 0x4a, 0x28, 0x7c]),
+            (40, [0xdf, 0xa6, 0x67, 0x47, 0xde, 0x9a, 0xe6, 0x30, 0x30, 0xca, 0x32, 0x61, 0x14, 0x97, 0xc8, 0x27]),
+            (64, [0x51, 0xf0, 0xbe, 0xbf, 0x7e, 0x3b, 0x9d, 0x92, 0xfc, 0x49, 0x74, 0x17, 0x79, 0x36, 0x3c, 0xfe]),
+        ];
+
+        for (len, tag) in cases {
+            assert_eq!(
+                mac(Aes::new(&key).unwrap(), &message[..len]),
+                tag,
+                "{}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_des_cmac() {
+        let key = 0x1334_5779_9BBC_DFF1;
+        let cases: [(&[u8], u64); 4] = [
+            (b"", 0x5467_455b_9d7c_5221),
+            (b"abcdefgh", 0x3aeb_1705_a3e4_1e44),
+            (b"sixteen-byte msg", 0x65ca_5412_214d_8ec1),
+            (b"nineteen-byte msg!!", 0x26cf_6caa_9223_9976),
+        ];
+
+        for (message, tag) in cases {
+            assert_eq!(mac(Des::new(key), message), tag.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_split_updates() {
+        let message = b"the MAC must not depend on how the input is split";
+        let whole = mac(Des::new(1), message);
+
+        let mut cmac = Cmac::new(Des::new(1));
+        for chunk in message.chunks(5) {
+            cmac.update(chunk);
+        }
+        assert_eq!(cmac.finalize(), whole);
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = mac(Des::new(1), b"message");
+
+        let mut cmac = Cmac::new(Des::new(1));
+        cmac.update(b"message");
+        assert_eq!(cmac.verify(&tag), Ok(()));
+
+        let mut cmac = Cmac::new(Des::new(1));
+        cmac.update(b"massage");
+        assert_eq!(cmac.verify(&tag), Err(Error::AuthenticationFailed));
+
+        let mut cmac = Cmac::new(Des::new(1));
+        cmac.update(b"message");
+        assert_eq!(cmac.verify(&tag[..7]), Err(Error::AuthenticationFailed));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::constant_time::ct_eq;
+use alloc::vec::Vec;
+
+/// Encrypted CBC-MAC (EMAC, ISO/IEC 9797-1 MAC algorithm 2).
+///
+/// The message is padded with `0x80` and zeros (padding method 2, which always
+/// adds at least one byte), CBC-MACed under the first key, and the final block
+/// is encrypted again under an independent second key. The extra encryption
+/// stops the length-extension forgeries that plain CBC-MAC allows.
+pub struct Emac<C: BlockCipher> {
+    inner: C,
+    outer: C,
+    /// CBC chaining value.
+    state: [u8; 16],
+    /// Pending bytes of the current block.
+    buf: [u8; 16],
+    buf_len: usize,
+}
+
+impl<C: BlockCipher> Emac<C> {
+    /// Creates an `Emac` that chains under `inner` and encrypts the result under `outer`.
+    /// The two ciphers must use independent keys.
+    pub fn new(inner: C, outer: C) -> Self {
+        assert!(C::BLOCK_SIZE <= 16, "block size cannot exceed 128 bits");
+
+        Self {
+            inner,
+            outer,
+            state: [0u8; 16],
+            buf: [0u8; 16],
+            buf_len: 0,
+        }
+    }
+
+    /// Feeds more message bytes into the MAC.
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.buf[self.buf_len] = b;
+            self.buf_len += 1;
+            if self.buf_len == C::BLOCK_SIZE {
+                self.absorb_buf();
+            }
+        }
+    }
+
+    /// Returns the tag, which is one block long.
+    pub fn finalize(mut self) -> Vec<u8> {
+        self.buf[self.buf_len] = 0x80;
+        self.buf[self.buf_len + 1..].fill(0);
+        self.absorb_buf();
+
+        self.outer.encrypt_block(&mut self.state[..C::BLOCK_SIZE]);
+        self.state[..C::BLOCK_SIZE].to_vec()
+    }
+
+    /// Computes the tag and compares it with `tag` in constant time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+
+    /// XORs the pending block into the chaining value and encrypts it.
+    fn absorb_buf(&mut self) {
+        for (s, b) in self.state.iter_mut().zip(self.buf) {
+            *s ^= b;
+        }
+        self.inner.encrypt_block(&mut self.state[..C::BLOCK_SIZE]);
+        self.buf_len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::aes::Aes;
+    use crate::block::des::Des;
+    use crate::modes::Cbc;
+
+    fn aes_emac() -> Emac<Aes> {
+        let k1 = Aes::new(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]).unwrap();
+        let k2 = Aes::new(&[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f]).unwrap();
+        Emac::new(k1, k2)
+    }
+
+    fn tag(data: &[u8]) -> Vec<u8> {
+        let mut emac = aes_emac();
+        emac.update(data);
+        emac.finalize()
+    }
+
+    /// Reference tags from OpenSSL's AES-128-CBC and AES-128-ECB.
+    #[test]
+    fn test_aes_emac() {
+        #[rustfmt::skip]
+        let cases: [(&[u8], [u8; 16]); 2] = [
+            (b"", [0x1d, 0xe3, 0x5e, 0xbc, 0xf4, 0x03, 0x2f, 0x41, 0x50, 0x41, 0x4f, 0x93, 0x23, 0x26, 0x66, 0xe7]),
+            (b"EMAC over several blocks of message text.", [0x8a, 0x3d, 0xef, 0x63, 0x91, 0x9e, 0x8a, 0x60, 0x3d, 0xc3, 0x8e, 0x59, 0x86, 0x48, 0xf4, 0xc9]),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(tag(message), expected);
+        }
+    }
+
+    #[test]
+    fn test_split_updates() {
+        let message = b"EMAC over several blocks of message text.";
+        let mut emac = aes_emac();
+        for chunk in message.chunks(3) {
+            emac.update(chunk);
+        }
+        assert_eq!(emac.finalize(), tag(message));
+    }
+
+    #[test]
+    fn test_length_extension() {
+        let des = || Des::new(0x1334_5779_9BBC_DFF1);
+        let block = *b"pay 100$";
+
+        // Plain CBC-MAC: the tag of `m` is also the tag of `m || (m ^ tag)`.
+        let cbc_mac = |data: &[u8]| {
+            let mut buf = data.to_vec();
+            Cbc::new(des(), &[0u8; 8])
+                .unwrap()
+                .encrypt(&mut buf)
+                .unwrap();
+            buf[buf.len() - 8..].to_vec()
+        };
+        let t = cbc_mac(&block);
+        let mut extended = block.to_vec();
+        extended.extend(block.iter().zip(&t).map(|(m, t)| m ^ t));
+        assert_eq!(cbc_mac(&extended), t);
+
+        // EMAC: the same splice does not carry the tag over.
+        let emac = |data: &[u8]| {
+            let mut emac = Emac::new(des(), Des::new(0x0E32_9232_EA6D_0D73));
+            emac.update(data);
+            emac.finalize()
+        };
+        let t = emac(&block);
+        let mut extended = block.to_vec();
+        extended.extend(block.iter().zip(&t).map(|(m, t)| m ^ t));
+        assert_ne!(emac(&extended), t);
+    }
+
+    #[test]
+    fn test_verify() {
+        let expected = tag(b"message");
+
+        let mut emac = aes_emac();
+        emac.update(b"message");
+        assert_eq!(emac.verify(&expected), Ok(()));
+
+        let mut emac = aes_emac();
+        emac.update(b"massage");
+        assert_eq!(emac.verify(&expected), Err(Error::AuthenticationFailed));
+    }
+}
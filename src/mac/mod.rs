@@ -0,0 +1,5 @@
+mod cmac;
+mod emac;
+
+pub use cmac::Cmac;
+pub use emac::Emac;
@@ -0,0 +1,107 @@
+use super::Cbc;
+use crate::Error;
+use crate::block::{BlockCipher, xor_in_place};
+
+/// CBC with residual block termination (RBT).
+///
+/// Full blocks go through CBC. A trailing partial block is XORed with the
+/// encryption of the last ciphertext block (the IV if there is none), as in
+/// CFB, so the output is exactly as long as the input and needs no padding.
+///
+/// Each `CbcRbt` handles one whole message, since a partial block can only come last.
+pub struct CbcRbt<C: BlockCipher> {
+    cipher: C,
+    iv: [u8; 16],
+}
+
+impl<C: BlockCipher> CbcRbt<C> {
+    /// Creates a `CbcRbt` from a cipher and an `iv` exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, Error> {
+        assert!(C::BLOCK_SIZE <= 16, "block size cannot exceed 128 bits");
+        if iv.len() != C::BLOCK_SIZE {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut stored = [0u8; 16];
+        stored[..C::BLOCK_SIZE].copy_from_slice(iv);
+        Ok(Self { cipher, iv: stored })
+    }
+
+    /// Encrypts the whole message in `buf` in place.
+    pub fn encrypt(self, buf: &mut [u8]) {
+        let (full, tail) = buf.split_at_mut(buf.len() / C::BLOCK_SIZE * C::BLOCK_SIZE);
+
+        let mut cbc = Cbc::new(&self.cipher, &self.iv[..C::BLOCK_SIZE]).expect("IV is one block");
+        cbc.encrypt(full).expect("full blocks are aligned");
+        self.apply_residual(cbc.current_iv(), tail);
+    }
+
+    /// Decrypts the whole message in `buf` in place.
+    pub fn decrypt(self, buf: &mut [u8]) {
+        let (full, tail) = buf.split_at_mut(buf.len() / C::BLOCK_SIZE * C::BLOCK_SIZE);
+
+        let mut cbc = Cbc::new(&self.cipher, &self.iv[..C::BLOCK_SIZE]).expect("IV is one block");
+        cbc.decrypt(full).expect("full blocks are aligned");
+        self.apply_residual(cbc.current_iv(), tail);
+    }
+
+    /// XORs the encryption of `last_block` into the partial `tail`.
+    fn apply_residual(&self, last_block: &[u8], tail: &mut [u8]) {
+        if tail.is_empty() {
+            return;
+        }
+        let mut keystream = [0u8; 16];
+        keystream[..C::BLOCK_SIZE].copy_from_slice(last_block);
+        self.cipher.encrypt_block(&mut keystream[..C::BLOCK_SIZE]);
+        xor_in_place(tail, &keystream);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+    const IV: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF];
+
+    fn rbt() -> CbcRbt<Des> {
+        CbcRbt::new(Des::new(KEY), &IV).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let message = b"residual block termination keeps the length";
+
+        for len in [0, 3, 8, 13, 16, message.len()] {
+            let mut buf = message[..len].to_vec();
+            rbt().encrypt(&mut buf);
+            assert_eq!(buf.len(), len);
+
+            rbt().decrypt(&mut buf);
+            assert_eq!(buf, &message[..len], "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_full_blocks_match_cbc() {
+        let message = b"twenty-one byte input";
+        let mut buf = message.to_vec();
+        rbt().encrypt(&mut buf);
+
+        let mut cbc = Cbc::new(Des::new(KEY), &IV).unwrap();
+        let mut full = message[..16].to_vec();
+        cbc.encrypt(&mut full).unwrap();
+        assert_eq!(buf[..16], full);
+
+        // The tail is CFB-style: plaintext XOR E(last ciphertext block).
+        let mut keystream = full[8..16].to_vec();
+        Des::new(KEY).encrypt_block(&mut keystream);
+        let tail: Vec<u8> = message[16..]
+            .iter()
+            .zip(&keystream)
+            .map(|(m, k)| m ^ k)
+            .collect();
+        assert_eq!(buf[16..], tail);
+    }
+}
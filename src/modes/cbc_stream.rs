@@ -0,0 +1,85 @@
+use super::{Cbc, RingEncryptor};
+use crate::Error;
+use crate::block::BlockCipher;
+use alloc::vec::Vec;
+
+/// Incremental CBC encryption with PKCS#7 padding, for messages too large to
+/// hold in memory at once.
+///
+/// [`CbcEncryptor::update`] emits every complete ciphertext block and keeps the
+/// trailing partial block for the next call; [`CbcEncryptor::finalize`] pads
+/// and emits the last block. The output matches [`Cbc::encrypt_padded`] on the
+/// whole message, however it was split.
+pub struct CbcEncryptor<C: BlockCipher> {
+    stream: RingEncryptor<C>,
+}
+
+impl<C: BlockCipher> CbcEncryptor<C> {
+    /// Creates a `CbcEncryptor` from a cipher and an `iv` exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            stream: RingEncryptor::cbc(Cbc::new(cipher, iv)?),
+        })
+    }
+
+    /// Takes the next piece of plaintext and returns the ciphertext blocks it completes.
+    pub fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        self.stream.push(data)
+    }
+
+    /// Pads the buffered tail and returns the final ciphertext block.
+    pub fn finalize(self) -> Vec<u8> {
+        self.stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+    const IV: [u8; 8] = [7u8; 8];
+
+    #[test]
+    fn test_chunked_matches_single_call() {
+        let mut rng = StdRng::seed_from_u64(544);
+        let message: Vec<u8> = (0..1000u16).map(|i| (i * 7) as u8).collect();
+        let expected = Cbc::new(Des::new(KEY), &IV)
+            .unwrap()
+            .encrypt_padded(&message);
+
+        for _ in 0..20 {
+            let mut encryptor = CbcEncryptor::new(Des::new(KEY), &IV).unwrap();
+            let mut ciphertext = Vec::new();
+            let mut rest = &message[..];
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at(rng.gen_range(0..=rest.len().min(40)));
+                let out = encryptor.update(chunk);
+                assert!(out.len().is_multiple_of(8));
+                ciphertext.extend(out);
+                rest = tail;
+            }
+            ciphertext.extend(encryptor.finalize());
+
+            assert_eq!(ciphertext, expected);
+        }
+    }
+
+    #[test]
+    fn test_aligned_message_gets_padding_block() {
+        let mut encryptor = CbcEncryptor::new(Des::new(KEY), &IV).unwrap();
+        assert_eq!(encryptor.update(&[0u8; 16]).len(), 16);
+        assert_eq!(encryptor.finalize().len(), 8);
+    }
+
+    #[test]
+    fn test_invalid_iv() {
+        assert!(matches!(
+            CbcEncryptor::new(Des::new(KEY), &[0u8; 7]),
+            Err(Error::InvalidLength)
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::Error;
+use crate::block::BlockCipher;
+
+/// Full-block Cipher Feedback (CFB) mode.
+///
+/// The keystream is the encryption of the previous ciphertext block (the IV for
+/// the first block). Input of any length is accepted; a trailing partial block
+/// uses only as much keystream as it needs and the rest carries over to the next call.
+pub struct Cfb<C: BlockCipher> {
+    cipher: C,
+    /// Feedback register: the IV, then the ciphertext block being built.
+    register: [u8; 16],
+    keystream: [u8; 16],
+    /// Keystream bytes consumed from the current block.
+    used: usize,
+}
+
+impl<C: BlockCipher> Cfb<C> {
+    /// Creates a `Cfb` from a cipher and an `iv` exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, Error> {
+        assert!(C::BLOCK_SIZE <= 16, "block size cannot exceed 128 bits");
+        if iv.len() != C::BLOCK_SIZE {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut register = [0u8; 16];
+        register[..C::BLOCK_SIZE].copy_from_slice(iv);
+
+        Ok(Self {
+            cipher,
+            register,
+            keystream: [0u8; 16],
+            used: C::BLOCK_SIZE,
+        })
+    }
+
+    /// Returns the feedback register: the IV before any data, then the last
+    /// ciphertext block. After a whole number of blocks, use it as the IV to
+    /// resume the stream later; mid-block it holds part of the block being built.
+    pub fn current_iv(&self) -> &[u8] {
+        &self.register[..C::BLOCK_SIZE]
+    }
+
+    /// Encrypts `buf` in place.
+    pub fn encrypt(&mut self, buf: &mut [u8]) {
+        self.crypt(buf, false, |_| {});
+    }
+
+    /// Decrypts `buf` in place. Like encryption, this only uses the cipher's encrypt direction.
+    pub fn decrypt(&mut self, buf: &mut [u8]) {
+        self.crypt(buf, true, |_| {});
+    }
+
+    /// Encrypts `data` and also returns every feedback register value fed to the cipher.
+    #[cfg(feature = "debug-trace")]
+    pub fn encrypt_traced(&mut self, data: &[u8]) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut buf = data.to_vec();
+        let mut trace = Vec::new();
+        self.crypt(&mut buf, false, |register| trace.push(register.to_vec()));
+        (buf, trace)
+    }
+
+    /// Decrypts `data` and also returns every feedback register value fed to the cipher.
+    #[cfg(feature = "debug-trace")]
+    pub fn decrypt_traced(&mut self, data: &[u8]) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut buf = data.to_vec();
+        let mut trace = Vec::new();
+        self.crypt(&mut buf, true, |register| trace.push(register.to_vec()));
+        (buf, trace)
+    }
+
+    /// Runs CFB over `buf`, calling `on_feedback` with the register before each encryption.
+    fn crypt(&mut self, buf: &mut [u8], decrypt: bool, mut on_feedback: impl FnMut(&[u8])) {
+        for byte in buf {
+            if self.used == C::BLOCK_SIZE {
+                on_feedback(&self.register[..C::BLOCK_SIZE]);
+                self.keystream = self.register;
+                self.cipher
+                    .encrypt_block(&mut self.keystream[..C::BLOCK_SIZE]);
+                self.used = 0;
+            }
+
+            let input = *byte;
+            *byte ^= self.keystream[self.used];
+            // The ciphertext byte is fed back in both directions.
+            self.register[self.used] = if decrypt { input } else { *byte };
+            self.used += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+    const IV: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF];
+    const MESSAGE: &[u8] = b"feedback trace of 28 bytes!!";
+
+    fn cfb() -> Cfb<Des> {
+        Cfb::new(Des::new(KEY), &IV).unwrap()
+    }
+
+    /// Reference ciphertext from OpenSSL's DES-CFB.
+    #[rustfmt::skip]
+    const CIPHERTEXT: [u8; 28] = [
+        0x6f, 0xfc, 0xda, 0xf6, 0x89, 0x17, 0xd9, 0x65, 0x67, 0x0c, 0xad, 0xe3, 0x0b, 0xaa,
+        0x44, 0x80, 0x94, 0xe5, 0x4a, 0xd0, 0x0e, 0xdd, 0xb7, 0x6f, 0xc1, 0x66, 0xf2, 0x11,
+    ];
+
+    #[test]
+    fn test_known_answer() {
+        let mut buf = MESSAGE.to_vec();
+        cfb().encrypt(&mut buf);
+        assert_eq!(buf, CIPHERTEXT);
+
+        cfb().decrypt(&mut buf);
+        assert_eq!(buf, MESSAGE);
+    }
+
+    #[test]
+    fn test_split_calls() {
+        let mut cfb = cfb();
+        let mut buf = MESSAGE.to_vec();
+        let (head, tail) = buf.split_at_mut(11);
+        cfb.encrypt(head);
+        cfb.encrypt(tail);
+        assert_eq!(buf, CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_resume_from_current_iv() {
+        let mut first = cfb();
+        assert_eq!(first.current_iv(), IV);
+
+        let mut buf = MESSAGE.to_vec();
+        let (head, tail) = buf.split_at_mut(16);
+        first.encrypt(head);
+        assert_eq!(first.current_iv(), &CIPHERTEXT[8..16]);
+
+        let mut resumed = Cfb::new(Des::new(KEY), first.current_iv()).unwrap();
+        resumed.encrypt(tail);
+        assert_eq!(buf, CIPHERTEXT);
+
+        let mut decryptor = Cfb::new(Des::new(KEY), &CIPHERTEXT[8..16]).unwrap();
+        decryptor.decrypt(&mut buf[16..]);
+        assert_eq!(&buf[16..], &MESSAGE[16..]);
+    }
+
+    #[test]
+    fn test_invalid_iv() {
+        assert!(matches!(
+            Cfb::new(Des::new(KEY), &[0u8; 7]),
+            Err(Error::InvalidLength)
+        ));
+    }
+
+    #[cfg(feature = "debug-trace")]
+    #[test]
+    fn test_trace() {
+        let (ciphertext, trace) = cfb().encrypt_traced(MESSAGE);
+        assert_eq!(ciphertext, CIPHERTEXT);
+        assert_eq!(
+            trace,
+            [
+                IV.to_vec(),
+                CIPHERTEXT[..8].to_vec(),
+                CIPHERTEXT[8..16].to_vec(),
+                CIPHERTEXT[16..24].to_vec()
+            ]
+        );
+
+        let (plaintext, decrypt_trace) = cfb().decrypt_traced(&CIPHERTEXT);
+        assert_eq!(plaintext, MESSAGE);
+        assert_eq!(decrypt_trace, trace);
+    }
+}
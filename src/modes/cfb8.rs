@@ -0,0 +1,138 @@
+use crate::Error;
+use crate::block::BlockCipher;
+
+/// 8-bit Cipher Feedback (CFB-8) mode.
+///
+/// Every byte costs a whole block encryption: the shift register is encrypted,
+/// the first byte of the result is XORed into one input byte, and the register
+/// shifts left by a byte to take in the resulting ciphertext byte. Slower than
+/// full-block [`Cfb`](super::Cfb) and a different algorithm, kept for legacy
+/// protocols that require it.
+pub struct Cfb8<C: BlockCipher> {
+    cipher: C,
+    /// Shift register: the IV, then the most recent ciphertext bytes.
+    register: [u8; 16],
+}
+
+impl<C: BlockCipher> Cfb8<C> {
+    /// Creates a `Cfb8` from a cipher and an `iv` exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, Error> {
+        assert!(C::BLOCK_SIZE <= 16, "block size cannot exceed 128 bits");
+        if iv.len() != C::BLOCK_SIZE {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut register = [0u8; 16];
+        register[..C::BLOCK_SIZE].copy_from_slice(iv);
+
+        Ok(Self { cipher, register })
+    }
+
+    /// Returns the shift register: the IV, then the most recent ciphertext bytes.
+    /// Use it as the IV to resume the stream later, after any number of bytes.
+    pub fn current_iv(&self) -> &[u8] {
+        &self.register[..C::BLOCK_SIZE]
+    }
+
+    /// Encrypts `buf` in place.
+    pub fn encrypt(&mut self, buf: &mut [u8]) {
+        self.crypt(buf, false);
+    }
+
+    /// Decrypts `buf` in place. Like encryption, this only uses the cipher's encrypt direction.
+    pub fn decrypt(&mut self, buf: &mut [u8]) {
+        self.crypt(buf, true);
+    }
+
+    /// Runs CFB-8 over `buf`, one block encryption per byte.
+    fn crypt(&mut self, buf: &mut [u8], decrypt: bool) {
+        let register = &mut self.register[..C::BLOCK_SIZE];
+        for byte in buf {
+            let mut keystream = [0u8; 16];
+            keystream[..C::BLOCK_SIZE].copy_from_slice(register);
+            self.cipher.encrypt_block(&mut keystream[..C::BLOCK_SIZE]);
+
+            let input = *byte;
+            *byte ^= keystream[0];
+
+            // The ciphertext byte is fed back in both directions.
+            register.copy_within(1.., 0);
+            register[C::BLOCK_SIZE - 1] = if decrypt { input } else { *byte };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+
+    const KEY: u64 = 0x0123_4567_89AB_CDEF;
+    const IV: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF];
+
+    fn cfb8() -> Cfb8<Des> {
+        Cfb8::new(Des::new(KEY), &IV).unwrap()
+    }
+
+    /// FIPS 81, Appendix D, Table D3: 8-bit CFB.
+    #[test]
+    fn test_fips_81() {
+        let ciphertext = [
+            0xf3, 0x1f, 0xda, 0x07, 0x01, 0x14, 0x62, 0xee, 0x18, 0x7f, 0x43, 0xd8, 0x0a, 0x7c,
+            0xd9, 0xb5, 0xb0, 0xd2, 0x90, 0xda, 0x6e, 0x5b, 0x9a, 0x87,
+        ];
+
+        let mut buf = *b"Now is the time for all ";
+        cfb8().encrypt(&mut buf);
+        assert_eq!(buf, ciphertext);
+
+        cfb8().decrypt(&mut buf);
+        assert_eq!(&buf, b"Now is the time for all ");
+    }
+
+    #[test]
+    fn test_unaligned_round_trip() {
+        let message = b"eleven byte";
+        let mut buf = message.to_vec();
+
+        // Split calls carry the register over like a single call.
+        let mut split = cfb8();
+        let (head, tail) = buf.split_at_mut(4);
+        split.encrypt(head);
+        split.encrypt(tail);
+        assert_ne!(&buf[..], message);
+
+        let mut whole = message.to_vec();
+        cfb8().encrypt(&mut whole);
+        assert_eq!(buf, whole);
+
+        cfb8().decrypt(&mut buf);
+        assert_eq!(&buf[..], message);
+    }
+
+    #[test]
+    fn test_resume_from_current_iv() {
+        let mut buf = *b"Now is the time for all ";
+        let mut first = cfb8();
+        assert_eq!(first.current_iv(), IV);
+
+        let (head, tail) = buf.split_at_mut(11);
+        first.encrypt(head);
+        let mut resumed = Cfb8::new(Des::new(KEY), first.current_iv()).unwrap();
+        resumed.encrypt(tail);
+
+        let mut expected = *b"Now is the time for all ";
+        cfb8().encrypt(&mut expected);
+        assert_eq!(buf, expected);
+        // The register holds the last block's worth of ciphertext bytes.
+        assert_eq!(resumed.current_iv(), &expected[16..]);
+    }
+
+    #[test]
+    fn test_invalid_iv() {
+        assert!(matches!(
+            Cfb8::new(Des::new(KEY), &[0u8; 16]),
+            Err(Error::InvalidLength)
+        ));
+    }
+}
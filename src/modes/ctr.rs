@@ -0,0 +1,270 @@
+#[cfg(feature = "bytes")]
+use bytes::BytesMut;
+
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::stream::Keystream;
+
+/// Counter (CTR) mode.
+///
+/// Turns a block cipher into a stream cipher by encrypting successive counter
+/// blocks and XORing the result with the data. Encryption and decryption are
+/// the same operation.
+#[derive(Clone)]
+pub struct Ctr<C: BlockCipher> {
+    cipher: C,
+    /// Current counter block, right-aligned in 128 bits.
+    counter: u128,
+    /// Bits of the counter block that increment. The rest is a fixed nonce.
+    counter_mask: u128,
+    /// Keystream of the current counter block.
+    keystream: [u8; 16],
+    /// Number of keystream bytes already used from the current block.
+    used: usize,
+}
+
+impl<C: BlockCipher> Ctr<C> {
+    /// Creates a `Ctr` where the whole `counter_block` increments as one big-endian number.
+    /// The `counter_block` must be exactly one block long.
+    pub fn new(cipher: C, counter_block: &[u8]) -> Result<Self, Error> {
+        if counter_block.len() != C::BLOCK_SIZE {
+            return Err(Error::InvalidLength);
+        }
+        let counter = counter_block
+            .iter()
+            .fold(0u128, |acc, &b| (acc << 8) | b as u128);
+
+        Self::with_nonce_counter(cipher, 0, 0, counter)
+    }
+
+    /// Creates a `Ctr` whose counter block is split into a fixed nonce in the top
+    /// `nonce_bits` bits and a counter in the remaining low bits.
+    /// Only the counter field increments, wrapping around within its own width.
+    ///
+    /// Returns [`Error::InvalidLength`] if `nonce_bits` leaves no room for a
+    /// counter, or a value does not fit its field.
+    pub fn with_nonce_counter(
+        cipher: C,
+        nonce_bits: u32,
+        nonce_value: u128,
+        counter_start: u128,
+    ) -> Result<Self, Error> {
+        assert!(C::BLOCK_SIZE <= 16, "block size cannot exceed 128 bits");
+        let block_bits = (C::BLOCK_SIZE * 8) as u32;
+        if nonce_bits >= block_bits {
+            return Err(Error::InvalidLength);
+        }
+
+        let counter_bits = block_bits - nonce_bits;
+        let counter_mask = u128::MAX >> (128 - counter_bits);
+        if nonce_value >> nonce_bits != 0 || counter_start & !counter_mask != 0 {
+            return Err(Error::InvalidLength);
+        }
+
+        let nonce = if nonce_bits == 0 {
+            0
+        } else {
+            nonce_value << counter_bits
+        };
+
+        Ok(Self {
+            cipher,
+            counter: nonce | counter_start,
+            counter_mask,
+            keystream: [0u8; 16],
+            // No keystream is generated until the first byte is needed.
+            used: C::BLOCK_SIZE,
+        })
+    }
+
+    /// Returns the counter block that will produce the next keystream block.
+    pub fn counter(&self) -> u128 {
+        self.counter
+    }
+
+    /// Applies the keystream on the given buffer in place.
+    /// Use for both to encrypt and decrypt.
+    pub fn apply_keystream(&mut self, buf: &mut [u8]) {
+        for b in buf {
+            if self.used == C::BLOCK_SIZE {
+                self.refill_keystream();
+            }
+            *b ^= self.keystream[self.used];
+            self.used += 1;
+        }
+    }
+
+    /// Encrypts a `BytesMut` buffer in place, without copying it into a `Vec`.
+    #[cfg(feature = "bytes")]
+    pub fn encrypt_bytes_mut(&mut self, buf: &mut BytesMut) {
+        self.apply_keystream(buf);
+    }
+
+    /// Decrypts a `BytesMut` buffer in place, without copying it into a `Vec`.
+    #[cfg(feature = "bytes")]
+    pub fn decrypt_bytes_mut(&mut self, buf: &mut BytesMut) {
+        self.apply_keystream(buf);
+    }
+
+    /// Encrypts the current counter block and advances the counter field.
+    fn refill_keystream(&mut self) {
+        let block = &mut self.keystream[..C::BLOCK_SIZE];
+        block.copy_from_slice(&self.counter.to_be_bytes()[16 - C::BLOCK_SIZE..]);
+        self.cipher.encrypt_block(block);
+
+        let next = self.counter.wrapping_add(1) & self.counter_mask;
+        self.counter = (self.counter & !self.counter_mask) | next;
+        self.used = 0;
+    }
+}
+
+impl<C: BlockCipher> Keystream for Ctr<C> {
+    fn apply_keystream(&mut self, buf: &mut [u8]) {
+        Ctr::apply_keystream(self, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+
+    #[test]
+    fn test_round_trip() {
+        let message = b"counter mode needs no padding at all";
+        let mut buf = message.to_vec();
+
+        Ctr::new(Des::new(KEY), &[7u8; 8])
+            .unwrap()
+            .apply_keystream(&mut buf);
+        assert_ne!(buf, message);
+
+        Ctr::new(Des::new(KEY), &[7u8; 8])
+            .unwrap()
+            .apply_keystream(&mut buf);
+        assert_eq!(buf, message);
+    }
+
+    #[test]
+    fn test_keystream_is_encrypted_counter() {
+        let cipher = Des::new(KEY);
+        let mut ctr = Ctr::new(Des::new(KEY), &5u64.to_be_bytes()).unwrap();
+
+        let mut buf = [0u8; 16];
+        ctr.apply_keystream(&mut buf);
+
+        assert_eq!(buf[..8], cipher.encrypt(5).to_be_bytes());
+        assert_eq!(buf[8..], cipher.encrypt(6).to_be_bytes());
+    }
+
+    #[test]
+    fn test_split_calls_match_one_call() {
+        let mut whole = [0u8; 29];
+        Ctr::new(Des::new(KEY), &[0u8; 8])
+            .unwrap()
+            .apply_keystream(&mut whole);
+
+        let mut parts = [0u8; 29];
+        let mut ctr = Ctr::new(Des::new(KEY), &[0u8; 8]).unwrap();
+        for chunk in parts.chunks_mut(3) {
+            ctr.apply_keystream(chunk);
+        }
+
+        assert_eq!(whole, parts);
+    }
+
+    #[test]
+    fn test_nonce_stays_fixed() {
+        let nonce = 0xDEAD_BEEF;
+        let mut ctr = Ctr::with_nonce_counter(Des::new(KEY), 32, nonce, 0).unwrap();
+
+        for i in 1..=1000 {
+            ctr.apply_keystream(&mut [0u8; 8]);
+            assert_eq!(ctr.counter() >> 32, nonce);
+            assert_eq!(ctr.counter() & 0xFFFF_FFFF, i);
+        }
+    }
+
+    #[test]
+    fn test_counter_wraps_within_field() {
+        let nonce = 0xDEAD_BEEF;
+        let mut ctr = Ctr::with_nonce_counter(Des::new(KEY), 32, nonce, 0xFFFF_FFFE).unwrap();
+
+        ctr.apply_keystream(&mut [0u8; 16]);
+        assert_eq!(ctr.counter(), 0xDEAD_BEEF_0000_0000);
+
+        ctr.apply_keystream(&mut [0u8; 8]);
+        assert_eq!(ctr.counter(), 0xDEAD_BEEF_0000_0001);
+    }
+
+    #[test]
+    fn test_full_block_counter_wraps() {
+        let mut ctr = Ctr::new(Des::new(KEY), &[0xFF; 8]).unwrap();
+        ctr.apply_keystream(&mut [0u8; 8]);
+        assert_eq!(ctr.counter(), 0);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes_mut_round_trip() {
+        let message = b"framed network payload";
+        let mut buf = BytesMut::from(&message[..]);
+
+        Ctr::new(Des::new(KEY), &[1u8; 8])
+            .unwrap()
+            .encrypt_bytes_mut(&mut buf);
+        assert_ne!(&buf[..], message);
+
+        let mut expected = message.to_vec();
+        Ctr::new(Des::new(KEY), &[1u8; 8])
+            .unwrap()
+            .apply_keystream(&mut expected);
+        assert_eq!(&buf[..], expected);
+
+        Ctr::new(Des::new(KEY), &[1u8; 8])
+            .unwrap()
+            .decrypt_bytes_mut(&mut buf);
+        assert_eq!(&buf[..], message);
+    }
+
+    #[test]
+    fn test_invalid_counter_block_length() {
+        assert!(matches!(
+            Ctr::new(Des::new(KEY), &[0u8; 7]),
+            Err(Error::InvalidLength)
+        ));
+        assert!(matches!(
+            Ctr::new(Des::new(KEY), &[0u8; 16]),
+            Err(Error::InvalidLength)
+        ));
+    }
+
+    #[test]
+    fn test_oversized_nonce() {
+        let cases = [
+            // Nonce wider than its field.
+            (32, 1 << 32, 0),
+            // Counter wider than its field.
+            (32, 0, 1 << 32),
+            // No room left for a counter.
+            (64, 0, 0),
+            (200, 0, 0),
+            // A nonce with no bits must be zero.
+            (0, 1, 0),
+        ];
+        for (nonce_bits, nonce, counter) in cases {
+            assert!(
+                matches!(
+                    Ctr::with_nonce_counter(Des::new(KEY), nonce_bits, nonce, counter),
+                    Err(Error::InvalidLength)
+                ),
+                "{} {:#x} {:#x}",
+                nonce_bits,
+                nonce,
+                counter
+            );
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use super::Ctr;
+use crate::Error;
+use crate::block::BlockCipher;
+
+/// A CTR counter block layout: a fixed nonce on top and a counter of
+/// `counter_bits` bits below it, starting at `counter_start`.
+///
+/// [`Ctr::with_nonce_counter`] wraps the counter around within its field, which
+/// silently repeats keystream once a message outgrows it. [`CtrNonce::ctr`]
+/// checks the message length first and refuses messages that would wrap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CtrNonce {
+    nonce: u128,
+    counter_bits: u32,
+    counter_start: u128,
+}
+
+impl CtrNonce {
+    /// Creates a `CtrNonce` with a counter of `counter_bits` bits starting at `counter_start`.
+    ///
+    /// # Panics
+    /// If `counter_bits` is not between 1 and 128, or `counter_start` does not fit into it.
+    pub fn new(nonce: u128, counter_bits: u32, counter_start: u128) -> Self {
+        assert!(
+            (1..=128).contains(&counter_bits),
+            "counter must be between 1 and 128 bits"
+        );
+        assert!(
+            counter_start <= counter_max(counter_bits),
+            "counter does not fit into {} bits",
+            counter_bits
+        );
+
+        Self {
+            nonce,
+            counter_bits,
+            counter_start,
+        }
+    }
+
+    /// Returns how many more blocks the counter can produce before it wraps,
+    /// saturating at `u128::MAX` for a full 128-bit counter starting at zero.
+    pub fn remaining_blocks(&self) -> u128 {
+        (counter_max(self.counter_bits) - self.counter_start).saturating_add(1)
+    }
+
+    /// Checks that `len` bytes in blocks of `block_size` bytes fit into the
+    /// counter field. Returns [`Error::OutOfRange`] if the counter would wrap.
+    pub fn check_len(&self, len: usize, block_size: usize) -> Result<(), Error> {
+        let blocks = len.div_ceil(block_size) as u128;
+        // The last block uses counter `start + blocks - 1`, which must not pass the maximum.
+        if blocks > 0 && blocks - 1 > counter_max(self.counter_bits) - self.counter_start {
+            return Err(Error::OutOfRange);
+        }
+        Ok(())
+    }
+
+    /// Creates a `Ctr` for a message of `message_len` bytes under `cipher`.
+    ///
+    /// Returns [`Error::OutOfRange`] if the message would wrap the counter, and
+    /// [`Error::InvalidLength`] if the nonce does not fit above the counter in
+    /// the cipher's block. The check only covers `message_len` bytes: the
+    /// returned `Ctr` still wraps if it is fed more.
+    pub fn ctr<C: BlockCipher>(&self, cipher: C, message_len: usize) -> Result<Ctr<C>, Error> {
+        let block_bits = (C::BLOCK_SIZE * 8) as u32;
+        if self.counter_bits > block_bits {
+            return Err(Error::InvalidLength);
+        }
+        let nonce_bits = block_bits - self.counter_bits;
+
+        self.check_len(message_len, C::BLOCK_SIZE)?;
+        Ctr::with_nonce_counter(cipher, nonce_bits, self.nonce, self.counter_start)
+    }
+}
+
+/// Largest value of a counter `bits` wide.
+fn counter_max(bits: u32) -> u128 {
+    u128::MAX >> (128 - bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::aes::Aes;
+    use crate::block::des::Des;
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+
+    #[test]
+    fn test_overflow_boundary() {
+        // An 8-bit counter from zero covers exactly 256 DES blocks.
+        let split = CtrNonce::new(0xABCD, 8, 0);
+        assert_eq!(split.remaining_blocks(), 256);
+        assert_eq!(split.check_len(256 * 8, 8), Ok(()));
+        assert_eq!(split.check_len(256 * 8 + 1, 8), Err(Error::OutOfRange));
+
+        // Starting at 250 leaves six blocks, and a partial block counts as one.
+        let split = CtrNonce::new(0xABCD, 8, 250);
+        assert_eq!(split.remaining_blocks(), 6);
+        assert_eq!(split.check_len(6 * 8, 8), Ok(()));
+        assert_eq!(split.check_len(5 * 8 + 1, 8), Ok(()));
+        assert_eq!(split.check_len(6 * 8 + 1, 8), Err(Error::OutOfRange));
+
+        // The last counter value still has room for one block, and nothing is always fine.
+        let split = CtrNonce::new(0, 8, 255);
+        assert_eq!(split.check_len(8, 8), Ok(()));
+        assert_eq!(split.check_len(9, 8), Err(Error::OutOfRange));
+        assert_eq!(split.check_len(0, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_full_width_counter() {
+        let split = CtrNonce::new(0, 128, 0);
+        assert_eq!(split.remaining_blocks(), u128::MAX);
+        assert_eq!(split.check_len(usize::MAX, 16), Ok(()));
+
+        let split = CtrNonce::new(0, 128, u128::MAX);
+        assert_eq!(split.check_len(16, 16), Ok(()));
+        assert_eq!(split.check_len(17, 16), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn test_ctr_matches_manual_split() {
+        let message = b"nonce on top, counter below";
+        let mut expected = message.to_vec();
+        Ctr::with_nonce_counter(Des::new(KEY), 48, 0x1234_5678_9ABC, 7)
+            .unwrap()
+            .apply_keystream(&mut expected);
+
+        let mut ctr = CtrNonce::new(0x1234_5678_9ABC, 16, 7)
+            .ctr(Des::new(KEY), message.len())
+            .unwrap();
+        let mut buf = message.to_vec();
+        ctr.apply_keystream(&mut buf);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_ctr_rejects_bad_layout() {
+        // Four 8-byte blocks do not fit into a 2-bit counter starting at 1.
+        let split = CtrNonce::new(0, 2, 1);
+        assert_eq!(split.ctr(Des::new(KEY), 24).err(), None);
+        assert_eq!(split.ctr(Des::new(KEY), 25).err(), Some(Error::OutOfRange));
+
+        // A 96-bit counter does not fit into a DES block, nor a 41-bit nonce next to a 32-bit counter.
+        assert_eq!(
+            CtrNonce::new(0, 96, 0).ctr(Des::new(KEY), 8).err(),
+            Some(Error::InvalidLength)
+        );
+        assert_eq!(
+            CtrNonce::new(1 << 40, 32, 0).ctr(Des::new(KEY), 8).err(),
+            Some(Error::InvalidLength)
+        );
+
+        // The same layout fits AES's 128-bit block.
+        let aes = Aes::new(&[0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c]).unwrap();
+        assert!(CtrNonce::new(1 << 40, 32, 0).ctr(aes, 16).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "counter does not fit into 8 bits")]
+    fn test_counter_start_too_wide() {
+        CtrNonce::new(0, 8, 256);
+    }
+}
@@ -0,0 +1,72 @@
+mod cbc;
+mod cbc_rbt;
+mod cbc_stream;
+mod cfb;
+mod cfb8;
+mod ctr;
+mod ctr_nonce;
+mod ofb;
+mod ring;
+
+pub use cbc::Cbc;
+pub use cbc_rbt::CbcRbt;
+pub use cbc_stream::CbcEncryptor;
+pub use cfb::Cfb;
+pub use cfb8::Cfb8;
+pub use ctr::Ctr;
+pub use ctr_nonce::CtrNonce;
+pub use ofb::Ofb;
+pub use ring::{RingDecryptor, RingEncryptor};
+
+/// Block cipher modes of operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Electronic Codebook, padded with PKCS#7.
+    Ecb,
+    /// Cipher Block Chaining, padded with PKCS#7.
+    Cbc,
+    /// Cipher Feedback, a stream mode.
+    Cfb,
+    /// Output Feedback, a stream mode.
+    Ofb,
+    /// Counter, a stream mode.
+    Ctr,
+}
+
+impl Mode {
+    /// Reports whether the mode pads its input to whole blocks.
+    pub fn is_padded(self) -> bool {
+        matches!(self, Self::Ecb | Self::Cbc)
+    }
+
+    /// Returns how many block cipher calls `len` bytes of input take.
+    /// Padded modes always add a padding block, stream modes round up.
+    pub fn block_count(self, len: usize, block_size: usize) -> usize {
+        if self.is_padded() {
+            len / block_size + 1
+        } else {
+            len.div_ceil(block_size)
+        }
+    }
+}
+
+/// Whether a mode runs forwards or backwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_count() {
+        assert_eq!(Mode::Cbc.block_count(0, 16), 1);
+        assert_eq!(Mode::Cbc.block_count(15, 16), 1);
+        assert_eq!(Mode::Cbc.block_count(16, 16), 2);
+        assert_eq!(Mode::Ofb.block_count(0, 16), 0);
+        assert_eq!(Mode::Ofb.block_count(17, 16), 2);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::stream::Keystream;
+
+/// Output Feedback (OFB) mode.
+///
+/// The feedback register starts as the IV and is repeatedly encrypted; each raw
+/// cipher output is both the next keystream block and the next register value.
+/// The keystream never depends on the data, so encryption and decryption are the same.
+#[derive(Clone)]
+pub struct Ofb<C: BlockCipher> {
+    cipher: C,
+    /// Feedback register, which doubles as the current keystream block.
+    register: [u8; 16],
+    /// Keystream bytes consumed from the current block.
+    used: usize,
+}
+
+impl<C: BlockCipher> Ofb<C> {
+    /// Creates an `Ofb` from a cipher and an `iv` exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, Error> {
+        assert!(C::BLOCK_SIZE <= 16, "block size cannot exceed 128 bits");
+        if iv.len() != C::BLOCK_SIZE {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut register = [0u8; 16];
+        register[..C::BLOCK_SIZE].copy_from_slice(iv);
+
+        Ok(Self {
+            cipher,
+            register,
+            used: C::BLOCK_SIZE,
+        })
+    }
+
+    /// Returns the feedback register: the IV before any data, then the last
+    /// keystream block. After a whole number of blocks, use it as the IV to
+    /// resume the keystream later; mid-block, resuming from it would skip the
+    /// rest of the current block.
+    pub fn current_iv(&self) -> &[u8] {
+        &self.register[..C::BLOCK_SIZE]
+    }
+
+    /// XORs the keystream into `buf`, which encrypts or decrypts it.
+    pub fn apply_keystream(&mut self, buf: &mut [u8]) {
+        self.crypt(buf, |_| {});
+    }
+
+    /// Applies the keystream to `data` and also returns every feedback register
+    /// value fed to the cipher.
+    #[cfg(feature = "debug-trace")]
+    pub fn apply_keystream_traced(&mut self, data: &[u8]) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut buf = data.to_vec();
+        let mut trace = Vec::new();
+        self.crypt(&mut buf, |register| trace.push(register.to_vec()));
+        (buf, trace)
+    }
+
+    /// Runs OFB over `buf`, calling `on_feedback` with the register before each encryption.
+    fn crypt(&mut self, buf: &mut [u8], mut on_feedback: impl FnMut(&[u8])) {
+        for byte in buf {
+            if self.used == C::BLOCK_SIZE {
+                on_feedback(&self.register[..C::BLOCK_SIZE]);
+                self.cipher
+                    .encrypt_block(&mut self.register[..C::BLOCK_SIZE]);
+                self.used = 0;
+            }
+
+            *byte ^= self.register[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+impl<C: BlockCipher> Keystream for Ofb<C> {
+    fn apply_keystream(&mut self, buf: &mut [u8]) {
+        Ofb::apply_keystream(self, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+    const IV: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF];
+    const MESSAGE: &[u8] = b"feedback trace of 28 bytes!!";
+
+    fn ofb() -> Ofb<Des> {
+        Ofb::new(Des::new(KEY), &IV).unwrap()
+    }
+
+    /// Reference ciphertext from OpenSSL's DES-OFB.
+    #[rustfmt::skip]
+    const CIPHERTEXT: [u8; 28] = [
+        0x6f, 0xfc, 0xda, 0xf6, 0x89, 0x17, 0xd9, 0x65, 0x48, 0x2b, 0x60, 0x58, 0xa5, 0x46,
+        0x45, 0x68, 0xf4, 0xf6, 0x40, 0x4e, 0xf9, 0x66, 0xed, 0xd4, 0x76, 0xee, 0xa3, 0x07,
+    ];
+
+    #[test]
+    fn test_known_answer() {
+        let mut buf = MESSAGE.to_vec();
+        ofb().apply_keystream(&mut buf);
+        assert_eq!(buf, CIPHERTEXT);
+
+        ofb().apply_keystream(&mut buf);
+        assert_eq!(buf, MESSAGE);
+    }
+
+    #[test]
+    fn test_resume_from_current_iv() {
+        let mut first = ofb();
+        assert_eq!(first.current_iv(), IV);
+
+        let mut buf = MESSAGE.to_vec();
+        let (head, tail) = buf.split_at_mut(16);
+        first.apply_keystream(head);
+
+        // The register is the last keystream block, i.e. ciphertext XOR plaintext.
+        let keystream: Vec<u8> = CIPHERTEXT[8..16]
+            .iter()
+            .zip(&MESSAGE[8..16])
+            .map(|(c, m)| c ^ m)
+            .collect();
+        assert_eq!(first.current_iv(), keystream);
+
+        let mut resumed = Ofb::new(Des::new(KEY), first.current_iv()).unwrap();
+        resumed.apply_keystream(tail);
+        assert_eq!(buf, CIPHERTEXT);
+    }
+
+    #[cfg(feature = "debug-trace")]
+    #[test]
+    fn test_trace() {
+        let (ciphertext, trace) = ofb().apply_keystream_traced(MESSAGE);
+        assert_eq!(ciphertext, CIPHERTEXT);
+
+        // Each register after the IV is the previous raw cipher output.
+        let des = Des::new(KEY);
+        let mut expected = vec![IV.to_vec()];
+        for _ in 0..3 {
+            let mut next = expected.last().unwrap().clone();
+            des.encrypt_block(&mut next);
+            expected.push(next);
+        }
+        assert_eq!(trace, expected);
+    }
+}
@@ -0,0 +1,241 @@
+use super::{Cbc, Ctr};
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::padding::{pkcs7_pad, pkcs7_unpad};
+use alloc::vec::Vec;
+
+/// Mode wrapped by a [`RingEncryptor`] or [`RingDecryptor`].
+enum Inner<C: BlockCipher> {
+    Cbc(Cbc<C>),
+    Ctr(Ctr<C>),
+}
+
+/// Encrypts a continuous stream pushed in pieces of any size.
+///
+/// With CBC, complete blocks are emitted as soon as they fill up, the trailing
+/// partial block waits in a fixed-size buffer, and [`RingEncryptor::flush`] pads it.
+/// With CTR, every pushed byte is emitted right away.
+pub struct RingEncryptor<C: BlockCipher> {
+    inner: Inner<C>,
+    pending: [u8; 16],
+    pending_len: usize,
+}
+
+impl<C: BlockCipher> RingEncryptor<C> {
+    /// Streams through CBC with PKCS#7 padding.
+    pub fn cbc(cbc: Cbc<C>) -> Self {
+        Self {
+            inner: Inner::Cbc(cbc),
+            pending: [0u8; 16],
+            pending_len: 0,
+        }
+    }
+
+    /// Streams through CTR, which needs no buffering.
+    pub fn ctr(ctr: Ctr<C>) -> Self {
+        Self {
+            inner: Inner::Ctr(ctr),
+            pending: [0u8; 16],
+            pending_len: 0,
+        }
+    }
+
+    /// Takes more plaintext and returns whatever ciphertext is ready.
+    pub fn push(&mut self, input: &[u8]) -> Vec<u8> {
+        match &mut self.inner {
+            Inner::Ctr(ctr) => {
+                let mut out = input.to_vec();
+                ctr.apply_keystream(&mut out);
+                out
+            }
+            Inner::Cbc(cbc) => {
+                let mut out = Vec::with_capacity(self.pending_len + input.len());
+                for &b in input {
+                    self.pending[self.pending_len] = b;
+                    self.pending_len += 1;
+
+                    if self.pending_len == C::BLOCK_SIZE {
+                        let block = &mut self.pending[..C::BLOCK_SIZE];
+                        cbc.encrypt(block).expect("block is complete");
+                        out.extend_from_slice(block);
+                        self.pending_len = 0;
+                    }
+                }
+                out
+            }
+        }
+    }
+
+    /// Ends the stream. With CBC, returns the PKCS#7-padded final block.
+    pub fn flush(self) -> Vec<u8> {
+        match self.inner {
+            Inner::Ctr(_) => Vec::new(),
+            Inner::Cbc(mut cbc) => {
+                let mut last = self.pending[..self.pending_len].to_vec();
+                pkcs7_pad(&mut last, C::BLOCK_SIZE);
+                cbc.encrypt(&mut last).expect("padded block is complete");
+                last
+            }
+        }
+    }
+}
+
+/// Decrypts a stream produced by a [`RingEncryptor`], pushed in pieces of any size.
+///
+/// With CBC, the latest complete block is held back until more data arrives,
+/// since it might be the padded final block.
+pub struct RingDecryptor<C: BlockCipher> {
+    inner: Inner<C>,
+    pending: [u8; 16],
+    pending_len: usize,
+}
+
+impl<C: BlockCipher> RingDecryptor<C> {
+    /// Streams through CBC with PKCS#7 padding.
+    pub fn cbc(cbc: Cbc<C>) -> Self {
+        Self {
+            inner: Inner::Cbc(cbc),
+            pending: [0u8; 16],
+            pending_len: 0,
+        }
+    }
+
+    /// Streams through CTR, which needs no buffering.
+    pub fn ctr(ctr: Ctr<C>) -> Self {
+        Self {
+            inner: Inner::Ctr(ctr),
+            pending: [0u8; 16],
+            pending_len: 0,
+        }
+    }
+
+    /// Takes more ciphertext and returns whatever plaintext is ready.
+    pub fn push(&mut self, input: &[u8]) -> Vec<u8> {
+        match &mut self.inner {
+            Inner::Ctr(ctr) => {
+                let mut out = input.to_vec();
+                ctr.apply_keystream(&mut out);
+                out
+            }
+            Inner::Cbc(cbc) => {
+                let mut out = Vec::with_capacity(self.pending_len + input.len());
+                for &b in input {
+                    // Release the held block only once we know it is not the last one.
+                    if self.pending_len == C::BLOCK_SIZE {
+                        let block = &mut self.pending[..C::BLOCK_SIZE];
+                        cbc.decrypt(block).expect("block is complete");
+                        out.extend_from_slice(block);
+                        self.pending_len = 0;
+                    }
+                    self.pending[self.pending_len] = b;
+                    self.pending_len += 1;
+                }
+                out
+            }
+        }
+    }
+
+    /// Ends the stream. With CBC, decrypts the final block and strips its padding.
+    pub fn flush(self) -> Result<Vec<u8>, Error> {
+        match self.inner {
+            Inner::Ctr(_) => Ok(Vec::new()),
+            Inner::Cbc(mut cbc) => {
+                if self.pending_len != C::BLOCK_SIZE {
+                    return Err(Error::UnalignedInput);
+                }
+                let mut last = self.pending[..C::BLOCK_SIZE].to_vec();
+                cbc.decrypt(&mut last)?;
+                pkcs7_unpad(&mut last, C::BLOCK_SIZE)?;
+                Ok(last)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const KEY: u64 = 0x1334_5779_9BBC_DFF1;
+    const IV: [u8; 8] = [7u8; 8];
+
+    fn cbc() -> Cbc<Des> {
+        Cbc::new(Des::new(KEY), &IV).unwrap()
+    }
+
+    fn ctr() -> Ctr<Des> {
+        Ctr::new(Des::new(KEY), &IV).unwrap()
+    }
+
+    /// Splits `data` into pieces of random sizes, including empty ones.
+    fn random_pieces<'a>(rng: &mut StdRng, mut data: &'a [u8]) -> Vec<&'a [u8]> {
+        let mut pieces = Vec::new();
+        while !data.is_empty() {
+            let (piece, rest) = data.split_at(rng.gen_range(0..=data.len().min(20)));
+            pieces.push(piece);
+            data = rest;
+        }
+        pieces
+    }
+
+    #[test]
+    fn test_cbc_stream() {
+        let mut rng = StdRng::seed_from_u64(489);
+        let message: Vec<u8> = (0..300u16).map(|i| i as u8).collect();
+
+        let mut encryptor = RingEncryptor::cbc(cbc());
+        let mut ciphertext = Vec::new();
+        for piece in random_pieces(&mut rng, &message) {
+            ciphertext.extend(encryptor.push(piece));
+        }
+        ciphertext.extend(encryptor.flush());
+        assert_eq!(ciphertext, cbc().encrypt_padded(&message));
+
+        let mut decryptor = RingDecryptor::cbc(cbc());
+        let mut plaintext = Vec::new();
+        for piece in random_pieces(&mut rng, &ciphertext) {
+            plaintext.extend(decryptor.push(piece));
+        }
+        plaintext.extend(decryptor.flush().unwrap());
+        assert_eq!(plaintext, message);
+    }
+
+    #[test]
+    fn test_ctr_stream() {
+        let mut rng = StdRng::seed_from_u64(489);
+        let message: Vec<u8> = (0..300u16).map(|i| (i * 3) as u8).collect();
+
+        let mut encryptor = RingEncryptor::ctr(ctr());
+        let mut ciphertext = Vec::new();
+        for piece in random_pieces(&mut rng, &message) {
+            let out = encryptor.push(piece);
+            assert_eq!(out.len(), piece.len(), "CTR emits every byte right away");
+            ciphertext.extend(out);
+        }
+        assert!(encryptor.flush().is_empty());
+
+        let mut expected = message.clone();
+        ctr().apply_keystream(&mut expected);
+        assert_eq!(ciphertext, expected);
+
+        let mut decryptor = RingDecryptor::ctr(ctr());
+        let mut plaintext = Vec::new();
+        for piece in random_pieces(&mut rng, &ciphertext) {
+            plaintext.extend(decryptor.push(piece));
+        }
+        plaintext.extend(decryptor.flush().unwrap());
+        assert_eq!(plaintext, message);
+    }
+
+    #[test]
+    fn test_truncated_cbc_stream() {
+        let ciphertext = cbc().encrypt_padded(b"some message");
+
+        let mut decryptor = RingDecryptor::cbc(cbc());
+        decryptor.push(&ciphertext[..ciphertext.len() - 1]);
+        assert_eq!(decryptor.flush(), Err(Error::UnalignedInput));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::Error;
+use crate::block::aes::Aes;
+use crate::block::des::Des;
+use crate::block::{BlockCipher, TripleDes};
+use crate::modes::Cbc;
+use crate::stream::arc4::AllegedRc4;
+use alloc::vec::Vec;
+
+/// Algorithms reachable through the one-shot [`encrypt`] and [`decrypt`].
+///
+/// Block ciphers run in CBC mode with PKCS#7 padding and need a one-block IV.
+/// RC4 is a stream cipher and takes no IV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// DES with an 8-byte key.
+    Des,
+    /// Triple DES with a 24-byte key (three DES keys, first to last)
+    /// or a 16-byte two-key variant where the third key repeats the first.
+    TripleDes,
+    /// AES with a 16-byte key.
+    Aes128,
+    /// AES with a 24-byte key.
+    Aes192,
+    /// AES with a 32-byte key.
+    Aes256,
+    /// ARC4 with a non-empty key. As with [`AllegedRc4::new`], only the first
+    /// 256 bytes of a longer key are used.
+    Rc4,
+}
+
+/// Encrypts `data` with `algo` under `key`.
+///
+/// Fails with [`Error::InvalidKeyLength`] when `key` does not fit `algo`, and with
+/// [`Error::InvalidLength`] when `iv` is missing, mis-sized or given to RC4.
+pub fn encrypt(
+    algo: Algorithm,
+    key: &[u8],
+    iv: Option<&[u8]>,
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    match algo {
+        Algorithm::Rc4 => rc4(key, iv, data),
+        Algorithm::Des => Ok(cbc(des(key)?, iv)?.encrypt_padded(data)),
+        Algorithm::TripleDes => Ok(cbc(triple_des(key)?, iv)?.encrypt_padded(data)),
+        Algorithm::Aes128 | Algorithm::Aes192 | Algorithm::Aes256 => {
+            Ok(cbc(aes(algo, key)?, iv)?.encrypt_padded(data))
+        }
+    }
+}
+
+/// Decrypts `data` produced by [`encrypt`] with the same `algo`, `key` and `iv`.
+pub fn decrypt(
+    algo: Algorithm,
+    key: &[u8],
+    iv: Option<&[u8]>,
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    match algo {
+        Algorithm::Rc4 => rc4(key, iv, data),
+        Algorithm::Des => cbc(des(key)?, iv)?.decrypt_padded(data),
+        Algorithm::TripleDes => cbc(triple_des(key)?, iv)?.decrypt_padded(data),
+        Algorithm::Aes128 | Algorithm::Aes192 | Algorithm::Aes256 => {
+            cbc(aes(algo, key)?, iv)?.decrypt_padded(data)
+        }
+    }
+}
+
+fn cbc<C: BlockCipher>(cipher: C, iv: Option<&[u8]>) -> Result<Cbc<C>, Error> {
+    Cbc::new(cipher, iv.ok_or(Error::InvalidLength)?)
+}
+
+fn rc4(key: &[u8], iv: Option<&[u8]>, data: &[u8]) -> Result<Vec<u8>, Error> {
+    if key.is_empty() {
+        return Err(Error::InvalidKeyLength);
+    }
+    if iv.is_some() {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut buf = data.to_vec();
+    AllegedRc4::new(key).apply_keystream(&mut buf);
+    Ok(buf)
+}
+
+fn des(key: &[u8]) -> Result<Des, Error> {
+    let key: [u8; 8] = key.try_into().map_err(|_| Error::InvalidKeyLength)?;
+    Ok(Des::from_bytes(key))
+}
+
+fn triple_des(key: &[u8]) -> Result<TripleDes, Error> {
+    let keys: Vec<u64> = key
+        .chunks_exact(8)
+        .map(|k| u64::from_be_bytes(k.try_into().unwrap()))
+        .collect();
+
+    match (key.len(), &keys[..]) {
+        (24, &[k1, k2, k3]) => Ok(TripleDes::new(k1, k2, k3)),
+        (16, &[k1, k2]) => Ok(TripleDes::new_ede2(k1, k2)),
+        _ => Err(Error::InvalidKeyLength),
+    }
+}
+
+fn aes(algo: Algorithm, key: &[u8]) -> Result<Aes, Error> {
+    let expected = match algo {
+        Algorithm::Aes128 => 16,
+        Algorithm::Aes192 => 24,
+        _ => 32,
+    };
+    if key.len() != expected {
+        return Err(Error::InvalidKeyLength);
+    }
+    Aes::from_bytes(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"one entry point for every algorithm";
+
+    #[test]
+    fn test_round_trip() {
+        let key: Vec<u8> = (1..=32).collect();
+        let cases = [
+            (Algorithm::Des, 8, Some(8)),
+            (Algorithm::TripleDes, 24, Some(8)),
+            (Algorithm::TripleDes, 16, Some(8)),
+            (Algorithm::Aes128, 16, Some(16)),
+            (Algorithm::Aes192, 24, Some(16)),
+            (Algorithm::Aes256, 32, Some(16)),
+            (Algorithm::Rc4, 13, None),
+        ];
+
+        for (algo, key_len, iv_len) in cases {
+            let key = &key[..key_len];
+            let iv = iv_len.map(|len| vec![0xA5u8; len]);
+
+            let ciphertext = encrypt(algo, key, iv.as_deref(), MESSAGE).unwrap();
+            assert_ne!(ciphertext, MESSAGE, "{:?}", algo);
+            assert_eq!(
+                decrypt(algo, key, iv.as_deref(), &ciphertext).unwrap(),
+                MESSAGE,
+                "{:?}",
+                algo
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_direct_use() {
+        let key = 0x1334_5779_9BBC_DFF1u64;
+        let iv = [7u8; 8];
+        let expected = Cbc::new(Des::new(key), &iv)
+            .unwrap()
+            .encrypt_padded(MESSAGE);
+
+        assert_eq!(
+            encrypt(Algorithm::Des, &key.to_be_bytes(), Some(&iv), MESSAGE).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_key_length_mismatch() {
+        let key = [1u8; 33];
+        let cases = [
+            (Algorithm::Des, 16, Some(&[0u8; 8][..])),
+            (Algorithm::TripleDes, 20, Some(&[0u8; 8][..])),
+            (Algorithm::Aes128, 24, Some(&[0u8; 16][..])),
+            (Algorithm::Aes192, 16, Some(&[0u8; 16][..])),
+            (Algorithm::Aes256, 33, Some(&[0u8; 16][..])),
+            (Algorithm::Rc4, 0, None),
+        ];
+
+        for (algo, key_len, iv) in cases {
+            assert_eq!(
+                encrypt(algo, &key[..key_len], iv, MESSAGE),
+                Err(Error::InvalidKeyLength),
+                "{:?}",
+                algo
+            );
+        }
+    }
+
+    #[test]
+    fn test_rc4_long_key() {
+        // Keys past 256 bytes follow AllegedRc4::new: the extra bytes are ignored.
+        let key: Vec<u8> = (0..300u16).map(|i| (i * 7) as u8).collect();
+        let ciphertext = encrypt(Algorithm::Rc4, &key, None, MESSAGE).unwrap();
+        assert_eq!(
+            ciphertext,
+            encrypt(Algorithm::Rc4, &key[..256], None, MESSAGE).unwrap()
+        );
+
+        let mut expected = MESSAGE.to_vec();
+        AllegedRc4::new(&key).apply_keystream(&mut expected);
+        assert_eq!(ciphertext, expected);
+    }
+
+    #[test]
+    fn test_iv_mismatch() {
+        let key = [1u8; 16];
+        assert_eq!(
+            encrypt(Algorithm::Aes128, &key, None, MESSAGE),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            encrypt(Algorithm::Aes128, &key, Some(&[0u8; 8]), MESSAGE),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            encrypt(Algorithm::Rc4, &key, Some(&[0u8; 8]), MESSAGE),
+            Err(Error::InvalidLength)
+        );
+    }
+}
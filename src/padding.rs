@@ -0,0 +1,323 @@
+use crate::Error;
+use crate::constant_time::ct_select;
+use alloc::vec::Vec;
+
+/// A block padding scheme, for modes that let the caller pick one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding {
+    /// Every pad byte equals the pad length. See [`pkcs7_pad`].
+    #[default]
+    Pkcs7,
+    /// Zero bytes followed by the pad length. See [`ansi_x923_pad`].
+    AnsiX923,
+    /// Zero bytes only, and none when the data is aligned. See [`zero_pad`].
+    Zero,
+}
+
+impl Padding {
+    /// Appends this padding to `buf`.
+    pub fn pad(self, buf: &mut Vec<u8>, block_size: usize) {
+        match self {
+            Self::Pkcs7 => pkcs7_pad(buf, block_size),
+            Self::AnsiX923 => ansi_x923_pad(buf, block_size),
+            Self::Zero => zero_pad(buf, block_size),
+        }
+    }
+
+    /// Validates and strips this padding from `buf`.
+    pub fn unpad(self, buf: &mut Vec<u8>, block_size: usize) -> Result<(), Error> {
+        match self {
+            Self::Pkcs7 => pkcs7_unpad(buf, block_size),
+            Self::AnsiX923 => ansi_x923_unpad(buf, block_size),
+            Self::Zero => zero_unpad(buf, block_size),
+        }
+    }
+}
+
+/// Appends PKCS#7 padding to `buf`.
+/// Each pad byte equals the pad length, and a full block is added when `buf` is already aligned.
+pub fn pkcs7_pad(buf: &mut Vec<u8>, block_size: usize) {
+    assert!(
+        (1..=255).contains(&block_size),
+        "block size must be between 1 and 255 bytes"
+    );
+
+    let pad_len = block_size - buf.len() % block_size;
+    buf.resize(buf.len() + pad_len, pad_len as u8);
+}
+
+/// Validates and strips PKCS#7 padding from `buf`.
+/// On error `buf` is left untouched.
+pub fn pkcs7_unpad(buf: &mut Vec<u8>, block_size: usize) -> Result<(), Error> {
+    let len = pkcs7_unpadded_len(buf, block_size)?;
+    buf.truncate(len);
+    Ok(())
+}
+
+/// Validates the PKCS#7 padding of `buf` and returns the length of the data before it.
+pub(crate) fn pkcs7_unpadded_len(buf: &[u8], block_size: usize) -> Result<usize, Error> {
+    if buf.is_empty() || !buf.len().is_multiple_of(block_size) {
+        return Err(Error::InvalidPadding);
+    }
+
+    let pad_len = buf[buf.len() - 1] as usize;
+    if pad_len == 0 || pad_len > block_size {
+        return Err(Error::InvalidPadding);
+    }
+
+    let pad_start = buf.len() - pad_len;
+    if buf[pad_start..].iter().any(|&b| b as usize != pad_len) {
+        return Err(Error::InvalidPadding);
+    }
+
+    Ok(pad_start)
+}
+
+/// Returns the PKCS#7 padding length of the final `block`, or zero when the
+/// padding is malformed. Every byte of `block` is checked whatever the padding
+/// holds, and the result is picked without branching on the padding bytes.
+///
+/// # Panics
+///
+/// Panics if `block` is empty or longer than 255 bytes.
+pub(crate) fn pkcs7_pad_len_masked(block: &[u8]) -> u8 {
+    assert!(
+        (1..=255).contains(&block.len()),
+        "block must be 1 to 255 bytes"
+    );
+
+    let pad_len = block[block.len() - 1];
+    let mut bad = (pad_len == 0) | (pad_len as usize > block.len());
+    for (i, &b) in block.iter().rev().enumerate() {
+        // The byte `i` places from the end is padding when `i < pad_len`.
+        bad |= ((i as u8) < pad_len) & (b != pad_len);
+    }
+    ct_select(bad, 0, pad_len)
+}
+
+/// Appends ANSI X.923 padding to `buf`: zero bytes, then a final byte holding
+/// the pad length. Like PKCS#7, a full block is added when `buf` is already aligned.
+pub fn ansi_x923_pad(buf: &mut Vec<u8>, block_size: usize) {
+    assert!(
+        (1..=255).contains(&block_size),
+        "block size must be between 1 and 255 bytes"
+    );
+
+    let pad_len = block_size - buf.len() % block_size;
+    buf.resize(buf.len() + pad_len - 1, 0);
+    buf.push(pad_len as u8);
+}
+
+/// Validates and strips ANSI X.923 padding from `buf`.
+/// On error `buf` is left untouched.
+pub fn ansi_x923_unpad(buf: &mut Vec<u8>, block_size: usize) -> Result<(), Error> {
+    if buf.is_empty() || !buf.len().is_multiple_of(block_size) {
+        return Err(Error::InvalidPadding);
+    }
+
+    let pad_len = buf[buf.len() - 1] as usize;
+    if pad_len == 0 || pad_len > block_size {
+        return Err(Error::InvalidPadding);
+    }
+
+    let pad_start = buf.len() - pad_len;
+    if buf[pad_start..buf.len() - 1].iter().any(|&b| b != 0) {
+        return Err(Error::InvalidPadding);
+    }
+
+    buf.truncate(pad_start);
+    Ok(())
+}
+
+/// Appends zero bytes to `buf` up to the next multiple of `block_size`.
+/// Aligned input, including empty input, is left as is.
+pub fn zero_pad(buf: &mut Vec<u8>, block_size: usize) {
+    assert!(block_size > 0, "block size must be positive");
+    buf.resize(buf.len().next_multiple_of(block_size), 0);
+}
+
+/// Strips zero padding from `buf`: the trailing zero bytes of its last block,
+/// at most `block_size - 1` of them.
+///
+/// Zero padding is ambiguous: data that ends in zero bytes loses them, since
+/// they cannot be told apart from padding. Only use it for data that never
+/// ends in a zero byte, such as text. The only malformed input is a length
+/// that is not a multiple of `block_size`, which leaves `buf` untouched.
+pub fn zero_unpad(buf: &mut Vec<u8>, block_size: usize) -> Result<(), Error> {
+    assert!(block_size > 0, "block size must be positive");
+    if !buf.len().is_multiple_of(block_size) {
+        return Err(Error::InvalidPadding);
+    }
+
+    let zeros = buf
+        .iter()
+        .rev()
+        .take(block_size - 1)
+        .take_while(|&&b| b == 0)
+        .count();
+    buf.truncate(buf.len() - zeros);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkcs7_round_trip() {
+        for len in 0..20 {
+            let message: Vec<u8> = (0..len).collect();
+            let mut buf = message.clone();
+
+            pkcs7_pad(&mut buf, 8);
+            assert!(buf.len().is_multiple_of(8) && buf.len() > message.len());
+
+            pkcs7_unpad(&mut buf, 8).expect("padding should be valid");
+            assert_eq!(buf, message);
+        }
+    }
+
+    #[test]
+    fn test_pkcs7_pad_bytes() {
+        let mut buf = vec![0xAA; 5];
+        pkcs7_pad(&mut buf, 8);
+        assert_eq!(buf, [0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_pkcs7_pad_aligned() {
+        // Aligned input gets a whole block of padding, so unpadding is never ambiguous.
+        let mut buf = vec![0xAA; 16];
+        pkcs7_pad(&mut buf, 16);
+        assert_eq!(buf.len(), 32);
+        assert!(buf[16..].iter().all(|&b| b == 16));
+
+        pkcs7_unpad(&mut buf, 16).unwrap();
+        assert_eq!(buf, [0xAA; 16]);
+    }
+
+    #[test]
+    fn test_pkcs7_unpad_rejects_malformed() {
+        assert_eq!(pkcs7_unpad(&mut vec![], 8), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7_unpad(&mut vec![1; 7], 8), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7_unpad(&mut vec![0; 8], 8), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7_unpad(&mut vec![9; 8], 8), Err(Error::InvalidPadding));
+
+        let mut tampered = vec![0, 0, 0, 0, 0, 3, 2, 3];
+        assert_eq!(pkcs7_unpad(&mut tampered, 8), Err(Error::InvalidPadding));
+        assert_eq!(tampered.len(), 8, "buffer is untouched on error");
+    }
+
+    #[test]
+    fn test_pkcs7_pad_len_masked() {
+        assert_eq!(
+            pkcs7_pad_len_masked(&[0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 3, 3, 3]),
+            3
+        );
+        assert_eq!(pkcs7_pad_len_masked(&[8; 8]), 8);
+        assert_eq!(pkcs7_pad_len_masked(&[0; 8]), 0);
+        assert_eq!(pkcs7_pad_len_masked(&[9; 8]), 0);
+        assert_eq!(pkcs7_pad_len_masked(&[0, 0, 0, 0, 0, 3, 2, 3]), 0);
+    }
+
+    #[test]
+    fn test_ansi_x923_pad_bytes() {
+        let mut buf = vec![0xAA; 5];
+        ansi_x923_pad(&mut buf, 8);
+        assert_eq!(buf, [0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0, 0, 3]);
+
+        ansi_x923_unpad(&mut buf, 8).unwrap();
+        assert_eq!(buf, [0xAA; 5]);
+    }
+
+    #[test]
+    fn test_ansi_x923_aligned() {
+        let mut buf = vec![0xAA; 8];
+        ansi_x923_pad(&mut buf, 8);
+        assert_eq!(buf[8..], [0, 0, 0, 0, 0, 0, 0, 8]);
+
+        ansi_x923_unpad(&mut buf, 8).unwrap();
+        assert_eq!(buf, [0xAA; 8]);
+    }
+
+    #[test]
+    fn test_ansi_x923_unpad_rejects_malformed() {
+        assert_eq!(ansi_x923_unpad(&mut vec![], 8), Err(Error::InvalidPadding));
+        assert_eq!(
+            ansi_x923_unpad(&mut vec![1; 7], 8),
+            Err(Error::InvalidPadding)
+        );
+        assert_eq!(
+            ansi_x923_unpad(&mut vec![0; 8], 8),
+            Err(Error::InvalidPadding)
+        );
+        assert_eq!(
+            ansi_x923_unpad(&mut vec![9; 8], 8),
+            Err(Error::InvalidPadding)
+        );
+
+        // PKCS#7 padding is not valid X.923: the pad bytes must be zero.
+        let mut pkcs7 = vec![0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 3, 3, 3];
+        assert_eq!(ansi_x923_unpad(&mut pkcs7, 8), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7.len(), 8, "buffer is untouched on error");
+    }
+
+    #[test]
+    fn test_zero_padding() {
+        let mut buf = vec![0xAA; 5];
+        zero_pad(&mut buf, 8);
+        assert_eq!(buf, [0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0, 0, 0]);
+        zero_unpad(&mut buf, 8).unwrap();
+        assert_eq!(buf, [0xAA; 5]);
+
+        // Aligned input gets no padding at all.
+        let mut aligned = vec![0xAA; 8];
+        zero_pad(&mut aligned, 8);
+        assert_eq!(aligned, [0xAA; 8]);
+        zero_unpad(&mut aligned, 8).unwrap();
+        assert_eq!(aligned, [0xAA; 8]);
+
+        let mut empty = Vec::new();
+        zero_pad(&mut empty, 8);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_zero_unpad_is_ambiguous() {
+        // Trailing zeros in the data are stripped along with the padding.
+        let mut buf = vec![0xAA, 0xAA, 0, 0];
+        zero_pad(&mut buf, 8);
+        zero_unpad(&mut buf, 8).unwrap();
+        assert_eq!(buf, [0xAA, 0xAA]);
+
+        // A final all-zero block cannot be padding, so one zero byte survives.
+        let mut zeros = vec![0; 8];
+        zero_unpad(&mut zeros, 8).unwrap();
+        assert_eq!(zeros, [0]);
+
+        assert_eq!(zero_unpad(&mut vec![0; 7], 8), Err(Error::InvalidPadding));
+    }
+
+    #[test]
+    #[should_panic(expected = "block size must be positive")]
+    fn test_zero_unpad_zero_block_size() {
+        let _ = zero_unpad(&mut Vec::new(), 0);
+    }
+
+    #[test]
+    fn test_padding_enum() {
+        for padding in [Padding::Pkcs7, Padding::AnsiX923, Padding::Zero] {
+            for len in 0..20 {
+                let message: Vec<u8> = (1..=len).collect();
+                let mut buf = message.clone();
+
+                padding.pad(&mut buf, 8);
+                assert!(buf.len().is_multiple_of(8));
+
+                padding.unpad(&mut buf, 8).unwrap();
+                assert_eq!(buf, message, "{:?}", padding);
+            }
+        }
+        assert_eq!(Padding::default(), Padding::Pkcs7);
+    }
+}
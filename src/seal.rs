@@ -0,0 +1,235 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use rand_core::RngCore;
+
+use crate::Error;
+use crate::block::BlockCipher;
+use crate::block::aes::Aes;
+use crate::block::des::Des;
+use crate::mac::Cmac;
+use crate::modes::{Cbc, Ctr};
+
+/// A block cipher that can be identified in a sealed header.
+pub trait Sealable: BlockCipher {
+    /// Identifier stored in the high nibble of the header byte. Must fit in 4 bits.
+    const CIPHER_ID: u8;
+}
+
+impl Sealable for Des {
+    const CIPHER_ID: u8 = 1;
+}
+
+impl Sealable for Aes {
+    const CIPHER_ID: u8 = 2;
+}
+
+/// Mode of operation and padding recorded in the low nibble of the header byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// CBC mode with PKCS#7 padding.
+    CbcPkcs7 = 1,
+    /// CTR mode, which needs no padding.
+    Ctr = 2,
+}
+
+impl Scheme {
+    fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            1 => Ok(Self::CbcPkcs7),
+            2 => Ok(Self::Ctr),
+            _ => Err(Error::UnknownHeader),
+        }
+    }
+}
+
+/// Encrypts `data` into a self-describing message: `header || iv || ciphertext`.
+///
+/// The 1-byte header records the cipher and the `scheme`, and the IV is drawn
+/// from `rng`, so [`open`] needs nothing but the cipher to decrypt it.
+pub fn seal<C: Sealable, R: RngCore>(
+    cipher: &C,
+    scheme: Scheme,
+    rng: &mut R,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut iv = vec![0u8; C::BLOCK_SIZE];
+    rng.fill_bytes(&mut iv);
+
+    let ciphertext = match scheme {
+        Scheme::CbcPkcs7 => Cbc::new(cipher, &iv)
+            .expect("IV is one block long")
+            .encrypt_padded(data),
+        Scheme::Ctr => {
+            let mut buf = data.to_vec();
+            Ctr::new(cipher, &iv)
+                .expect("IV is one block long")
+                .apply_keystream(&mut buf);
+            buf
+        }
+    };
+
+    let mut sealed = Vec::with_capacity(1 + iv.len() + ciphertext.len());
+    sealed.push((C::CIPHER_ID << 4) | scheme as u8);
+    sealed.extend(iv);
+    sealed.extend(ciphertext);
+    sealed
+}
+
+/// Decrypts a message produced by [`seal`], reading the mode and padding from its header.
+pub fn open<C: Sealable>(cipher: &C, sealed: &[u8]) -> Result<Vec<u8>, Error> {
+    let (&header, rest) = sealed.split_first().ok_or(Error::InvalidLength)?;
+    if header >> 4 != C::CIPHER_ID {
+        return Err(Error::UnknownHeader);
+    }
+    let scheme = Scheme::from_id(header & 0x0F)?;
+
+    if rest.len() < C::BLOCK_SIZE {
+        return Err(Error::InvalidLength);
+    }
+    let (iv, ciphertext) = rest.split_at(C::BLOCK_SIZE);
+
+    match scheme {
+        Scheme::CbcPkcs7 => Cbc::new(cipher, iv)?.decrypt_padded(ciphertext),
+        Scheme::Ctr => {
+            let mut buf = ciphertext.to_vec();
+            Ctr::new(cipher, iv)?.apply_keystream(&mut buf);
+            Ok(buf)
+        }
+    }
+}
+
+/// Like [`seal`], but also authenticates `aad`, which stays readable and travels
+/// separately from the returned `sealed || tag` message.
+///
+/// This is encrypt-then-MAC: the CMAC under `mac_key` covers the length of `aad`,
+/// `aad` itself and the sealed ciphertext.
+pub fn seal_with_aad<C: Sealable, M: BlockCipher, R: RngCore>(
+    cipher: &C,
+    mac_key: &M,
+    rng: &mut R,
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Vec<u8> {
+    let mut sealed = seal(cipher, Scheme::Ctr, rng, plaintext);
+    let tag = aad_tag(mac_key, aad, &sealed).finalize();
+    sealed.extend(tag);
+    sealed
+}
+
+/// Verifies the tag over `aad` and the ciphertext, then decrypts a message
+/// produced by [`seal_with_aad`].
+pub fn open_with_aad<C: Sealable, M: BlockCipher>(
+    cipher: &C,
+    mac_key: &M,
+    aad: &[u8],
+    sealed: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if sealed.len() < M::BLOCK_SIZE {
+        return Err(Error::InvalidLength);
+    }
+    let (sealed, tag) = sealed.split_at(sealed.len() - M::BLOCK_SIZE);
+
+    aad_tag(mac_key, aad, sealed).verify(tag)?;
+    open(cipher, sealed)
+}
+
+/// Starts a CMAC over `len(aad) || aad || sealed`.
+/// The length prefix stops bytes from shifting between `aad` and the ciphertext.
+fn aad_tag<'a, M: BlockCipher>(mac_key: &'a M, aad: &[u8], sealed: &[u8]) -> Cmac<&'a M> {
+    let mut cmac = Cmac::new(mac_key);
+    cmac.update(&(aad.len() as u64).to_be_bytes());
+    cmac.update(aad);
+    cmac.update(sealed);
+    cmac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const MESSAGE: &[u8] = b"self-describing ciphertext";
+
+    fn aes() -> Aes {
+        Aes::new(&[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f]).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+
+        for scheme in [Scheme::CbcPkcs7, Scheme::Ctr] {
+            let sealed = seal(&des, scheme, &mut rng, MESSAGE);
+            assert_eq!(sealed[0], 0x10 | scheme as u8);
+            assert_eq!(open(&des, &sealed).unwrap(), MESSAGE);
+
+            let sealed = seal(&aes(), scheme, &mut rng, MESSAGE);
+            assert_eq!(sealed[0], 0x20 | scheme as u8);
+            assert_eq!(open(&aes(), &sealed).unwrap(), MESSAGE);
+        }
+    }
+
+    #[test]
+    fn test_random_iv() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+
+        let first = seal(&des, Scheme::CbcPkcs7, &mut rng, MESSAGE);
+        let second = seal(&des, Scheme::CbcPkcs7, &mut rng, MESSAGE);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_unknown_header() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+        let mut sealed = seal(&des, Scheme::Ctr, &mut rng, MESSAGE);
+
+        sealed[0] = 0x1F;
+        assert_eq!(open(&des, &sealed), Err(Error::UnknownHeader));
+
+        // Sealed with DES, opened with AES.
+        sealed[0] = 0x12;
+        assert_eq!(open(&aes(), &sealed), Err(Error::UnknownHeader));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let des = Des::new(0x1334_5779_9BBC_DFF1);
+        assert_eq!(open(&des, &[]), Err(Error::InvalidLength));
+        assert_eq!(open(&des, &[0x12, 0, 0]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    fn test_aad_round_trip() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (des, mac_key) = (Des::new(0x1334_5779_9BBC_DFF1), aes());
+        let aad = b"to: alice";
+
+        let sealed = seal_with_aad(&des, &mac_key, &mut rng, aad, MESSAGE);
+        assert_eq!(
+            open_with_aad(&des, &mac_key, aad, &sealed).unwrap(),
+            MESSAGE
+        );
+    }
+
+    #[test]
+    fn test_aad_tampering() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (des, mac_key) = (Des::new(0x1334_5779_9BBC_DFF1), aes());
+        let mut sealed = seal_with_aad(&des, &mac_key, &mut rng, b"to: alice", MESSAGE);
+
+        assert_eq!(
+            open_with_aad(&des, &mac_key, b"to: mallory", &sealed),
+            Err(Error::AuthenticationFailed)
+        );
+
+        sealed[20] ^= 1;
+        assert_eq!(
+            open_with_aad(&des, &mac_key, b"to: alice", &sealed),
+            Err(Error::AuthenticationFailed)
+        );
+    }
+}
@@ -1,7 +1,26 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{ErrorKind, Read, Write};
+
+use rand_core::RngCore;
+
+use super::Keystream;
+use crate::Error;
+
+/// Cloning snapshots the whole state, so a clone continues the keystream from
+/// the same point as the original. `Copy` is deliberately not derived, as
+/// silently duplicated keystream is keystream reuse.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AllegedRc4 {
     i: u8,
     j: u8,
+    #[cfg_attr(feature = "serde", serde(with = "permutation"))]
     s: [u8; 256],
+    /// Keystream bytes generated so far. 64 bits wide so streams past 4 GiB
+    /// are counted correctly on every platform.
+    position: u64,
 }
 
 impl AllegedRc4 {
@@ -24,7 +43,12 @@ impl AllegedRc4 {
         }
 
         // Set i and j to zero.
-        Self { i: 0, j: 0, s }
+        Self {
+            i: 0,
+            j: 0,
+            s,
+            position: 0,
+        }
     }
 
     // Applies the ARC4 keystream on the given buffer in place.
@@ -35,9 +59,155 @@ impl AllegedRc4 {
         }
     }
 
+    /// Returns `data` with the keystream applied, leaving `data` untouched.
+    /// Advances the stream exactly like [`AllegedRc4::apply_keystream`].
+    pub fn encrypt(&mut self, data: &[u8]) -> Vec<u8> {
+        data.iter().map(|&b| self.process_byte(b)).collect()
+    }
+
+    /// Same as `apply_keystream`, but over a fixed-size buffer so the compiler
+    /// can unroll the loop for small inputs like 8 or 16-byte headers.
+    pub fn apply_keystream_n<const N: usize>(&mut self, buf: &mut [u8; N]) {
+        for b in buf.iter_mut() {
+            *b = self.process_byte(*b);
+        }
+    }
+
+    /// Applies the keystream to 32-bit words in place, XORing 4 keystream bytes
+    /// into each word in big-endian order (the first byte lands in the top bits).
+    pub fn apply_keystream_u32(&mut self, words: &mut [u32]) {
+        for w in words {
+            let mut bytes = w.to_be_bytes();
+            self.apply_keystream(&mut bytes);
+            *w = u32::from_be_bytes(bytes);
+        }
+    }
+
+    /// Applies the keystream and returns the Adler-32 checksum of the resulting
+    /// ciphertext, computed in the same pass.
+    ///
+    /// The checksum only catches accidental corruption. Anyone can recompute it
+    /// after tampering, so it is not a MAC.
+    pub fn apply_keystream_checksummed(&mut self, buf: &mut [u8]) -> u32 {
+        const MOD_ADLER: u32 = 65521;
+
+        let (mut a, mut b) = (1u32, 0u32);
+        for byte in buf {
+            *byte = self.process_byte(*byte);
+            a = (a + *byte as u32) % MOD_ADLER;
+            b = (b + a) % MOD_ADLER;
+        }
+        (b << 16) | a
+    }
+
+    /// Saves `count` states of the `key` keystream, one every `stride` bytes
+    /// starting at offset 0, for [`AllegedRc4::from_nearest_snapshot`].
+    pub fn snapshot_table(key: &[u8], stride: u64, count: usize) -> Vec<AllegedRc4> {
+        assert!(stride > 0, "stride cannot be zero");
+
+        let mut cipher = AllegedRc4::new(key);
+        let mut table = Vec::with_capacity(count);
+        for _ in 0..count {
+            table.push(cipher.clone());
+            cipher.advance(stride);
+        }
+        table
+    }
+
+    /// Returns a cipher positioned at keystream `offset`, starting from the closest
+    /// snapshot at or before it and generating only the remaining bytes.
+    ///
+    /// `stride` must be the one `table` was built with. Returns
+    /// [`Error::InvalidLength`] if the table is empty, `stride` is zero, or the
+    /// chosen snapshot does not sit where `stride` says it should.
+    pub fn from_nearest_snapshot(
+        table: &[AllegedRc4],
+        stride: u64,
+        offset: u64,
+    ) -> Result<Self, Error> {
+        if table.is_empty() || stride == 0 {
+            return Err(Error::InvalidLength);
+        }
+
+        let index = (offset / stride).min(table.len() as u64 - 1);
+        let mut cipher = table[index as usize].clone();
+        if cipher.position != index * stride {
+            return Err(Error::InvalidLength);
+        }
+        cipher.advance(offset - cipher.position);
+        Ok(cipher)
+    }
+
+    /// Creates an RC4-drop[`n`] cipher: keyed like [`AllegedRc4::new`], but with
+    /// the first `n` keystream bytes discarded, as they are the most biased.
+    /// Common values are 256, 768 and 3072. Dropped bytes count towards
+    /// [`AllegedRc4::position`].
+    pub fn new_drop(k: &[u8], n: usize) -> Self {
+        let mut cipher = Self::new(k);
+        cipher.skip(n);
+        cipher
+    }
+
+    /// Returns how many keystream bytes have been generated so far.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Discards the next `n` keystream bytes, running the PRGA without output.
+    ///
+    /// This takes time linear in `n`: RC4 has no random access, so the only way
+    /// to a later keystream position is through every step before it.
+    pub fn skip(&mut self, n: usize) {
+        self.advance(n as u64);
+    }
+
+    /// Same as [`AllegedRc4::skip`], with a count that may exceed `usize` on
+    /// 32-bit targets.
+    fn advance(&mut self, n: u64) {
+        for _ in 0..n {
+            self.keystream_byte();
+        }
+    }
+
+    /// Returns the next keystream byte, the same as `process_byte(0)`.
+    pub fn keystream_byte(&mut self) -> u8 {
+        self.process_byte(0)
+    }
+
+    /// Overwrites `out` with the next `out.len()` keystream bytes.
+    pub fn fill_keystream(&mut self, out: &mut [u8]) {
+        for b in out {
+            *b = self.keystream_byte();
+        }
+    }
+
+    /// Turns the cipher into an endless iterator over its keystream bytes.
+    /// It never returns `None`, so bound it with e.g. `take`.
+    pub fn into_keystream(mut self) -> impl Iterator<Item = u8> {
+        core::iter::repeat_with(move || self.keystream_byte())
+    }
+
+    /// Returns the next `len` keystream bytes.
+    pub fn keystream_vec(&mut self, len: usize) -> Vec<u8> {
+        let mut keystream = vec![0u8; len];
+        self.fill_keystream(&mut keystream);
+        keystream
+    }
+
+    /// Returns an endless iterator that lazily yields `chunk_size`-byte keystream chunks,
+    /// e.g. to pace encrypted output to the network one MTU at a time.
+    pub fn chunks(&mut self, chunk_size: usize) -> KeystreamChunks<'_> {
+        assert!(chunk_size > 0, "chunk size cannot be zero");
+        KeystreamChunks {
+            cipher: self,
+            chunk_size,
+        }
+    }
+
     // Generates one keystream byte and XORs it with the input.
     // Implements Pseudo-Random Generation Algorithm (PRGA).
     pub fn process_byte(&mut self, pb: u8) -> u8 {
+        self.position += 1;
         self.i = self.i.wrapping_add(1);
         self.j = self.j.wrapping_add(self.s[self.i as usize]);
 
@@ -53,6 +223,131 @@ impl AllegedRc4 {
     }
 }
 
+impl Keystream for AllegedRc4 {
+    fn apply_keystream(&mut self, buf: &mut [u8]) {
+        AllegedRc4::apply_keystream(self, buf);
+    }
+}
+
+/// Reads `reader` to the end, applies the keystream of `cipher` and writes the
+/// result to `writer`, so files or sockets can be piped through without loading
+/// them into memory. Short reads are fine; interrupted reads are retried.
+/// Other I/O failures are returned as [`Error::Io`].
+#[cfg(feature = "std")]
+pub fn process_stream<R: Read, W: Write>(
+    cipher: &mut AllegedRc4,
+    mut reader: R,
+    mut writer: W,
+) -> Result<(), Error> {
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        cipher.apply_keystream(&mut buf[..n]);
+        writer.write_all(&buf[..n])?;
+    }
+    Ok(writer.flush()?)
+}
+
+/// Reports whether a 3-byte WEP-style `iv` has the Fluhrer-Mantin-Shamir weak form
+/// `(A + 3, 255, X)`, which leaks key byte `A` through the first keystream byte.
+/// `A` covers the 13 secret bytes of a 104-bit WEP key.
+pub fn is_fms_weak_iv(iv: &[u8]) -> bool {
+    matches!(iv, [a, 255, _] if (3..3 + 13).contains(a))
+}
+
+/// Tallies keystream byte `index` (0 is the first byte) over `samples` ciphers
+/// keyed with random `key_len`-byte keys from `rng`, to show RC4's biases.
+/// E.g. the second byte (index 1) is 0 twice as often as uniform (Mantin-Shamir).
+///
+/// It takes `rng` because the crate has no randomness source of its own in
+/// `no_std`, and `index` because with random keys the first byte is within a
+/// fraction of a percent of uniform; the strong single-byte bias is in the second.
+pub fn keystream_byte_distribution<R: RngCore>(
+    rng: &mut R,
+    key_len: usize,
+    index: usize,
+    samples: usize,
+) -> [u64; 256] {
+    let mut key = vec![0u8; key_len];
+    let mut counts = [0u64; 256];
+
+    for _ in 0..samples {
+        rng.fill_bytes(&mut key);
+        let mut cipher = AllegedRc4::new(&key);
+        cipher.skip(index);
+        counts[cipher.keystream_byte() as usize] += 1;
+    }
+    counts
+}
+
+/// Serializes the 256-byte state table, which is larger than serde's built-in
+/// array support, and rejects tables that are not permutations when reading.
+#[cfg(feature = "serde")]
+mod permutation {
+    use core::fmt;
+
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::ser::SerializeTuple;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(s: &[u8; 256], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(256)?;
+        for b in s {
+            tuple.serialize_element(b)?;
+        }
+        tuple.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 256], D::Error> {
+        struct PermutationVisitor;
+
+        impl<'de> Visitor<'de> for PermutationVisitor {
+            type Value = [u8; 256];
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a permutation of the 256 byte values")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut s = [0u8; 256];
+                let mut seen = [false; 256];
+                for (i, b) in s.iter_mut().enumerate() {
+                    *b = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                    if seen[*b as usize] {
+                        return Err(de::Error::custom("state table repeats a byte value"));
+                    }
+                    seen[*b as usize] = true;
+                }
+                Ok(s)
+            }
+        }
+
+        deserializer.deserialize_tuple(256, PermutationVisitor)
+    }
+}
+
+/// Iterator over keystream chunks, created by [`AllegedRc4::chunks`].
+pub struct KeystreamChunks<'a> {
+    cipher: &'a mut AllegedRc4,
+    chunk_size: usize,
+}
+
+impl Iterator for KeystreamChunks<'_> {
+    type Item = Vec<u8>;
+
+    // The keystream never ends.
+    fn next(&mut self) -> Option<Vec<u8>> {
+        Some(self.cipher.keystream_vec(self.chunk_size))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +379,386 @@ mod tests {
         decipher.apply_keystream(&mut ciphertext);
         assert_eq!(ciphertext, message, "Message should be decrypted");
     }
+
+    #[test]
+    fn test_chunks() {
+        let chunk_size = 1500;
+        let mut cipher = AllegedRc4::new(SEED);
+        let chunks: Vec<Vec<u8>> = cipher.chunks(chunk_size).take(3).collect();
+
+        assert!(chunks.iter().all(|chunk| chunk.len() == chunk_size));
+        assert_eq!(
+            chunks.concat(),
+            AllegedRc4::new(SEED).keystream_vec(3 * chunk_size)
+        );
+    }
+
+    #[test]
+    fn test_apply_keystream_u32() {
+        let mut words = [0x0123_4567u32, 0x89AB_CDEF, 0, u32::MAX];
+        let mut bytes: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+
+        AllegedRc4::new(SEED).apply_keystream_u32(&mut words);
+        AllegedRc4::new(SEED).apply_keystream(&mut bytes);
+
+        let expected: Vec<u32> = bytes
+            .chunks_exact(4)
+            .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(words.to_vec(), expected);
+    }
+
+    #[test]
+    fn test_encrypt_to_vec() {
+        let message = b"keep the plaintext around";
+        let mut cipher = AllegedRc4::new(SEED);
+        cipher.keystream_vec(7);
+        let mut in_place = cipher.clone();
+
+        let ciphertext = cipher.encrypt(message);
+        let mut buf = message.to_vec();
+        in_place.apply_keystream(&mut buf);
+        assert_eq!(ciphertext, buf);
+
+        assert_eq!(cipher.position(), in_place.position());
+        assert_eq!(cipher.keystream_vec(8), in_place.keystream_vec(8));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip() {
+        let mut cipher = AllegedRc4::new(SEED);
+        cipher.keystream_vec(1000);
+
+        let json = serde_json::to_string(&cipher).unwrap();
+        let mut resumed: AllegedRc4 = serde_json::from_str(&json).unwrap();
+        assert_eq!(resumed.position(), 1000);
+        assert_eq!(resumed.keystream_vec(64), cipher.keystream_vec(64));
+
+        // A state table that is not a permutation is refused.
+        let broken = json.replacen("\"s\":[", "\"s\":[0,", 1);
+        assert!(serde_json::from_str::<AllegedRc4>(&broken).is_err());
+    }
+
+    #[test]
+    fn test_clone_mid_stream() {
+        let mut cipher = AllegedRc4::new(SEED);
+        cipher.keystream_vec(100);
+
+        let mut fork = cipher.clone();
+        assert_eq!(fork.position(), cipher.position());
+        assert_eq!(fork.keystream_vec(64), cipher.keystream_vec(64));
+
+        // The two are independent from then on.
+        fork.keystream_vec(1);
+        assert_ne!(fork.keystream_vec(16), cipher.keystream_vec(16));
+    }
+
+    #[test]
+    fn test_skip() {
+        for n in [0, 1, 255, 256, 1000] {
+            let mut skipped = AllegedRc4::new(SEED);
+            skipped.skip(n);
+            assert_eq!(skipped.position(), n as u64);
+
+            let mut stepped = AllegedRc4::new(SEED);
+            let mut last = 0;
+            for _ in 0..=n {
+                last = stepped.keystream_byte();
+            }
+            assert_eq!(skipped.keystream_byte(), last);
+        }
+    }
+
+    #[test]
+    fn test_into_keystream() {
+        let mut cipher = AllegedRc4::new(SEED);
+        let expected: Vec<u8> = (0..16).map(|_| cipher.keystream_byte()).collect();
+
+        let keystream: Vec<u8> = AllegedRc4::new(SEED).into_keystream().take(16).collect();
+        assert_eq!(keystream, expected);
+
+        // Zipping with data encrypts it.
+        let message = b"zip and xor";
+        let ciphertext: Vec<u8> = AllegedRc4::new(SEED)
+            .into_keystream()
+            .zip(message)
+            .map(|(k, m)| k ^ m)
+            .collect();
+        assert_eq!(ciphertext, AllegedRc4::new(SEED).encrypt(message));
+    }
+
+    #[test]
+    fn test_keystream_vectors() {
+        let cases: [(&[u8], [u8; 10]); 3] = [
+            (
+                b"Key",
+                [0xeb, 0x9f, 0x77, 0x81, 0xb7, 0x34, 0xca, 0x72, 0xa7, 0x19],
+            ),
+            (
+                b"Wiki",
+                [0x60, 0x44, 0xdb, 0x6d, 0x41, 0xb7, 0xe8, 0xe7, 0xa4, 0xd6],
+            ),
+            (
+                b"Secret",
+                [0x04, 0xd4, 0x6b, 0x05, 0x3c, 0xa8, 0x7b, 0x59, 0x41, 0x72],
+            ),
+        ];
+
+        for (key, expected) in cases {
+            let mut cipher = AllegedRc4::new(key);
+            assert_eq!(cipher.keystream_byte(), expected[0]);
+
+            let mut rest = [0xAAu8; 9];
+            cipher.fill_keystream(&mut rest);
+            assert_eq!(rest, expected[1..]);
+            assert_eq!(cipher.position(), 10);
+        }
+    }
+
+    #[test]
+    fn test_cross_platform_determinism() {
+        // RFC 6229, 40-bit key 0x0102030405, keystream offsets 0..32.
+        #[rustfmt::skip]
+        const REFERENCE: [u8; 32] = [
+            0xb2, 0x39, 0x63, 0x05, 0xf0, 0x3d, 0xc0, 0x27, 0xcc, 0xc3, 0x52, 0x4a, 0x0a, 0x11, 0x18, 0xa8,
+            0x69, 0x82, 0x94, 0x4f, 0x18, 0xfc, 0x82, 0xd5, 0x89, 0xc4, 0x03, 0xa4, 0x7a, 0x0d, 0x09, 0x19,
+        ];
+
+        let mut cipher = AllegedRc4::new(&[0x01, 0x02, 0x03, 0x04, 0x05]);
+        assert_eq!(cipher.keystream_vec(32), REFERENCE);
+    }
+
+    #[test]
+    fn test_apply_keystream_n() {
+        let mut fixed: [u8; 16] = *b"sixteen byte hdr";
+        let mut slice = fixed.to_vec();
+
+        let mut cipher = AllegedRc4::new(SEED);
+        cipher.apply_keystream_n::<16>(&mut fixed);
+        AllegedRc4::new(SEED).apply_keystream(&mut slice);
+        assert_eq!(fixed.to_vec(), slice);
+
+        // The state advances exactly as with the slice version.
+        let mut next = [0u8; 8];
+        cipher.apply_keystream_n(&mut next);
+        assert_eq!(next.to_vec(), AllegedRc4::new(SEED).keystream_vec(24)[16..]);
+    }
+
+    #[test]
+    fn test_snapshot_teleport() {
+        let message: Vec<u8> = (0..5000u32).map(|i| (i * 7) as u8).collect();
+        let mut ciphertext = message.clone();
+        AllegedRc4::new(SEED).apply_keystream(&mut ciphertext);
+
+        let stride = 1024;
+        let table = AllegedRc4::snapshot_table(SEED, stride, 4);
+        assert_eq!(table.len(), 4);
+
+        // Offsets inside, on and past the last snapshot.
+        for offset in [0, 1, 1023, 1024, 2500, 3072, 4999] {
+            let mut cipher =
+                AllegedRc4::from_nearest_snapshot(&table, stride, offset as u64).unwrap();
+            assert_eq!(cipher.position(), offset as u64);
+            let mut tail = ciphertext[offset..].to_vec();
+            cipher.apply_keystream(&mut tail);
+            assert_eq!(tail, message[offset..], "offset {}", offset);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_bad_stride() {
+        let table = AllegedRc4::snapshot_table(SEED, 1024, 4);
+
+        assert!(matches!(
+            AllegedRc4::from_nearest_snapshot(&table, 0, 100),
+            Err(Error::InvalidLength)
+        ));
+        assert!(matches!(
+            AllegedRc4::from_nearest_snapshot(&[], 1024, 100),
+            Err(Error::InvalidLength)
+        ));
+
+        // A stride other than the table's lands on the wrong snapshot.
+        for stride in [512, 2048] {
+            assert!(matches!(
+                AllegedRc4::from_nearest_snapshot(&table, stride, 2500),
+                Err(Error::InvalidLength)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_apply_keystream_checksummed() {
+        // Closed form of Adler-32: A = 1 + sum(D_i), B = n + sum((n - i) * D_i).
+        fn adler32(data: &[u8]) -> u32 {
+            let n = data.len() as u64;
+            let a = 1 + data.iter().map(|&d| d as u64).sum::<u64>();
+            let b = n
+                + (0..n)
+                    .map(|i| (n - i) * data[i as usize] as u64)
+                    .sum::<u64>();
+            (((b % 65521) << 16) | (a % 65521)) as u32
+        }
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+        let mut buf = b"checksummed in the same pass".to_vec();
+        let checksum = AllegedRc4::new(SEED).apply_keystream_checksummed(&mut buf);
+        assert_eq!(checksum, adler32(&buf));
+
+        let mut expected = b"checksummed in the same pass".to_vec();
+        AllegedRc4::new(SEED).apply_keystream(&mut expected);
+        assert_eq!(buf, expected);
+
+        assert_eq!(
+            AllegedRc4::new(SEED).apply_keystream_checksummed(&mut []),
+            1
+        );
+    }
+
+    #[test]
+    fn test_position() {
+        let mut cipher = AllegedRc4::new(SEED);
+        assert_eq!(cipher.position(), 0);
+
+        cipher.apply_keystream(&mut [0u8; 10]);
+        cipher.apply_keystream_u32(&mut [0u32; 2]);
+        cipher.process_byte(0);
+        assert_eq!(cipher.position(), 19);
+    }
+
+    /// Generates a little over 4 GiB of keystream, which takes a while.
+    #[test]
+    #[ignore]
+    fn test_position_past_4_gib() {
+        let stride = 1u64 << 31;
+        let table = AllegedRc4::snapshot_table(SEED, stride, 3);
+        assert_eq!(table[2].position(), 1u64 << 32);
+
+        let offset = (1u64 << 32) + 5;
+        let mut cipher = AllegedRc4::from_nearest_snapshot(&table, stride, offset).unwrap();
+        assert_eq!(cipher.position(), offset);
+
+        let mut expected = table[2].clone();
+        expected.advance(5);
+        assert_eq!(cipher.keystream_vec(16), expected.keystream_vec(16));
+        assert_eq!(cipher.position(), offset + 16);
+    }
+
+    #[test]
+    fn test_keystream_byte_distribution() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(505);
+        let samples = 1 << 14;
+        let uniform = samples as u64 / 256;
+
+        // The first byte looks uniform at this sample size, bias towards 0 included.
+        let first = keystream_byte_distribution(&mut rng, 16, 0, samples);
+        assert_eq!(first.iter().sum::<u64>(), samples as u64);
+        assert!(first[0] < uniform * 3 / 2, "{} vs {}", first[0], uniform);
+        assert!(first.iter().all(|&count| count < uniform * 2));
+
+        // Mantin-Shamir: the second byte is 0 with probability about 2/256.
+        let second = keystream_byte_distribution(&mut rng, 16, 1, samples);
+        assert_eq!(second.iter().sum::<u64>(), samples as u64);
+        assert!(second[0] > uniform * 3 / 2, "{} vs {}", second[0], uniform);
+
+        // The third byte has no such bias towards 0.
+        let third = keystream_byte_distribution(&mut rng, 16, 2, samples);
+        assert!(third[0] < uniform * 3 / 2, "{} vs {}", third[0], uniform);
+    }
+
+    #[test]
+    fn test_is_fms_weak_iv() {
+        assert!(is_fms_weak_iv(&[3, 255, 7]));
+        assert!(is_fms_weak_iv(&[15, 255, 0]));
+
+        assert!(!is_fms_weak_iv(&[0x12, 0x34, 0x56]));
+        assert!(!is_fms_weak_iv(&[2, 255, 7]));
+        assert!(!is_fms_weak_iv(&[16, 255, 7]));
+        assert!(!is_fms_weak_iv(&[3, 254, 7]));
+        assert!(!is_fms_weak_iv(&[3, 255]));
+    }
+
+    #[test]
+    fn test_new_drop() {
+        let message = b"skip the biased start of the keystream";
+
+        let mut plain = message.to_vec();
+        AllegedRc4::new(SEED).apply_keystream(&mut plain);
+
+        let mut dropped = message.to_vec();
+        AllegedRc4::new_drop(SEED, 256).apply_keystream(&mut dropped);
+        assert_ne!(dropped, plain);
+
+        let mut again = message.to_vec();
+        AllegedRc4::new_drop(SEED, 256).apply_keystream(&mut again);
+        assert_eq!(again, dropped);
+
+        // Dropping is the same as throwing away the first keystream bytes.
+        let keystream = AllegedRc4::new(SEED).keystream_vec(256 + message.len());
+        let expected: Vec<u8> = message
+            .iter()
+            .zip(&keystream[256..])
+            .map(|(m, k)| m ^ k)
+            .collect();
+        assert_eq!(dropped, expected);
+
+        assert_eq!(AllegedRc4::new_drop(SEED, 768).position(), 768);
+        assert_eq!(
+            AllegedRc4::new_drop(SEED, 0).keystream_vec(8),
+            AllegedRc4::new(SEED).keystream_vec(8)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_process_stream() {
+        use std::io::Cursor;
+
+        let message: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+
+        // Chaining two readers makes the first read stop short at the seam.
+        let reader = Cursor::new(&message[..1000]).chain(Cursor::new(&message[1000..]));
+        let mut ciphertext = Vec::new();
+        process_stream(&mut AllegedRc4::new(SEED), reader, &mut ciphertext).unwrap();
+
+        let mut expected = message.clone();
+        AllegedRc4::new(SEED).apply_keystream(&mut expected);
+        assert_eq!(ciphertext, expected);
+
+        let mut decrypted = Vec::new();
+        process_stream(
+            &mut AllegedRc4::new(SEED),
+            Cursor::new(&ciphertext),
+            &mut decrypted,
+        )
+        .unwrap();
+        assert_eq!(decrypted, message);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_process_stream_io_error() {
+        use std::error::Error as _;
+        use std::io;
+
+        struct BrokenPipe;
+
+        impl Write for BrokenPipe {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(ErrorKind::BrokenPipe, "peer went away"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = process_stream(&mut AllegedRc4::new(SEED), &b"data"[..], BrokenPipe).unwrap_err();
+        assert!(matches!(&err, Error::Io(e) if e.kind() == ErrorKind::BrokenPipe));
+
+        let source = err.source().expect("I/O error is kept as the source");
+        assert_eq!(source.to_string(), "peer went away");
+    }
 }
@@ -0,0 +1,224 @@
+use super::Keystream;
+
+/// "expand 32-byte k", the first row of every ChaCha20 block.
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// Bytes of keystream per block.
+const BLOCK_LEN: usize = 64;
+
+/// ChaCha20 as specified in RFC 8439: a 256-bit key, a 96-bit nonce and a
+/// 32-bit block counter.
+///
+/// Unlike [`AllegedRc4`](super::arc4::AllegedRc4), ChaCha20 is a secure stream
+/// cipher, as long as a nonce is never reused under the same key. Cloning
+/// continues the keystream from the same point, so `Copy` is not derived.
+#[derive(Clone)]
+pub struct ChaCha20 {
+    key: [u32; 8],
+    nonce: [u32; 3],
+    /// Counter of the next block to generate. Wider than the 32 bits of the
+    /// spec so running past the last block is detected instead of wrapping.
+    counter: u64,
+    block: [u8; BLOCK_LEN],
+    /// Bytes of `block` already used; `BLOCK_LEN` when it is exhausted.
+    offset: usize,
+}
+
+impl ChaCha20 {
+    /// Creates a `ChaCha20` keystream starting at block `counter`.
+    /// RFC 8439 starts at 1 for encryption, leaving block 0 for the Poly1305 key.
+    pub fn new(key: [u8; 32], nonce: [u8; 12], counter: u32) -> Self {
+        Self {
+            key: core::array::from_fn(|i| read_le(&key, i)),
+            nonce: core::array::from_fn(|i| read_le(&nonce, i)),
+            counter: counter as u64,
+            block: [0u8; BLOCK_LEN],
+            offset: BLOCK_LEN,
+        }
+    }
+
+    /// XORs the keystream into `buf` in place. Use for both to encrypt and decrypt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keystream runs past block 2^32 - 1, as the counter would
+    /// wrap around and repeat keystream.
+    pub fn apply_keystream(&mut self, buf: &mut [u8]) {
+        for b in buf {
+            if self.offset == BLOCK_LEN {
+                self.refill();
+            }
+            *b ^= self.block[self.offset];
+            self.offset += 1;
+        }
+    }
+
+    /// Moves the keystream to the start of block `counter`, forwards or backwards.
+    pub fn seek(&mut self, counter: u32) {
+        self.counter = counter as u64;
+        self.offset = BLOCK_LEN;
+    }
+
+    /// Generates the block at the current counter and advances it.
+    fn refill(&mut self) {
+        let counter = u32::try_from(self.counter).expect("ChaCha20 keystream exhausted");
+        self.block = block(&self.key, counter, &self.nonce);
+        self.counter += 1;
+        self.offset = 0;
+    }
+}
+
+impl Keystream for ChaCha20 {
+    fn apply_keystream(&mut self, buf: &mut [u8]) {
+        ChaCha20::apply_keystream(self, buf);
+    }
+}
+
+/// The ChaCha20 block function: 20 rounds over the initial state, which is
+/// then added back in and serialized little-endian.
+fn block(key: &[u32; 8], counter: u32, nonce: &[u32; 3]) -> [u8; BLOCK_LEN] {
+    let mut initial = [0u32; 16];
+    initial[..4].copy_from_slice(&CONSTANTS);
+    initial[4..12].copy_from_slice(key);
+    initial[12] = counter;
+    initial[13..].copy_from_slice(nonce);
+
+    let mut state = initial;
+    // Ten double rounds: one down the columns, one along the diagonals.
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut out = [0u8; BLOCK_LEN];
+    for (i, chunk) in out.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&state[i].wrapping_add(initial[i]).to_le_bytes());
+    }
+    out
+}
+
+/// Mixes four words of `state` with additions, XORs and rotations.
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// Reads the `i`-th little-endian 32-bit word of `bytes`.
+fn read_le(bytes: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::from_hex;
+
+    /// The key 00 01 02 ... 1f used throughout RFC 8439.
+    fn rfc_key() -> [u8; 32] {
+        core::array::from_fn(|i| i as u8)
+    }
+
+    const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you \
+        only one tip for the future, sunscreen would be it.";
+
+    /// RFC 8439, section 2.1.1.
+    #[test]
+    fn test_quarter_round() {
+        let mut state = [0u32; 16];
+        state[..4].copy_from_slice(&[0x1111_1111, 0x0102_0304, 0x9b8d_6f43, 0x0123_4567]);
+        quarter_round(&mut state, 0, 1, 2, 3);
+        assert_eq!(
+            state[..4],
+            [0xea2a_92f4, 0xcb1c_f8ce, 0x4581_472e, 0x5881_c4bb]
+        );
+    }
+
+    /// RFC 8439, section 2.3.2.
+    #[test]
+    fn test_block_function() {
+        let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let mut cipher = ChaCha20::new(rfc_key(), nonce, 1);
+        let mut keystream = [0u8; 64];
+        cipher.apply_keystream(&mut keystream);
+
+        let expected = from_hex(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e",
+        )
+        .unwrap();
+        assert_eq!(keystream[..], expected[..]);
+    }
+
+    /// RFC 8439, section 2.4.2.
+    #[test]
+    fn test_encrypt_sunscreen() {
+        let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let expected = from_hex(
+            "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+             f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+             07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+             5af90bbf74a35be6b40b8eedf2785e42874d",
+        )
+        .unwrap();
+
+        let mut buf = SUNSCREEN.to_vec();
+        ChaCha20::new(rfc_key(), nonce, 1).apply_keystream(&mut buf);
+        assert_eq!(buf, expected);
+
+        ChaCha20::new(rfc_key(), nonce, 1).apply_keystream(&mut buf);
+        assert_eq!(buf, SUNSCREEN);
+    }
+
+    #[test]
+    fn test_chunks_match_single_call() {
+        let nonce = [7u8; 12];
+        let mut whole = SUNSCREEN.to_vec();
+        ChaCha20::new(rfc_key(), nonce, 0).apply_keystream(&mut whole);
+
+        let mut cipher = ChaCha20::new(rfc_key(), nonce, 0);
+        let mut pieces = SUNSCREEN.to_vec();
+        for chunk in pieces.chunks_mut(13) {
+            cipher.apply_keystream(chunk);
+        }
+        assert_eq!(pieces, whole);
+    }
+
+    #[test]
+    fn test_seek() {
+        let nonce = [7u8; 12];
+        let mut keystream = [0u8; 3 * BLOCK_LEN];
+        ChaCha20::new(rfc_key(), nonce, 5).apply_keystream(&mut keystream);
+
+        let mut cipher = ChaCha20::new(rfc_key(), nonce, 5);
+        cipher.apply_keystream(&mut [0u8; 10]);
+        cipher.seek(7);
+        let mut third = [0u8; BLOCK_LEN];
+        cipher.apply_keystream(&mut third);
+        assert_eq!(third[..], keystream[2 * BLOCK_LEN..]);
+
+        // Seeking backwards replays earlier keystream.
+        cipher.seek(5);
+        let mut first = [0u8; BLOCK_LEN];
+        cipher.apply_keystream(&mut first);
+        assert_eq!(first[..], keystream[..BLOCK_LEN]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ChaCha20 keystream exhausted")]
+    fn test_counter_exhausted() {
+        let mut cipher = ChaCha20::new(rfc_key(), [0u8; 12], u32::MAX);
+        cipher.apply_keystream(&mut [0u8; BLOCK_LEN + 1]);
+    }
+}
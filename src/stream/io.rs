@@ -0,0 +1,204 @@
+use std::io::{self, Read, Write};
+
+use super::arc4::AllegedRc4;
+
+/// Bytes encrypted per call to the inner writer.
+const CHUNK: usize = 1024;
+
+/// A writer that encrypts with RC4 on the way through, created by
+/// [`AllegedRc4::writer`].
+pub struct Rc4Writer<W: Write> {
+    cipher: AllegedRc4,
+    inner: W,
+}
+
+impl AllegedRc4 {
+    /// Wraps `inner` so that everything written is encrypted before being passed on.
+    /// The keystream carries on across calls, as if the data were written at once.
+    pub fn writer<W: Write>(self, inner: W) -> Rc4Writer<W> {
+        Rc4Writer {
+            cipher: self,
+            inner,
+        }
+    }
+}
+
+/// A reader that decrypts RC4 ciphertext as it is read, created by
+/// [`AllegedRc4::reader`].
+pub struct Rc4Reader<R: Read> {
+    cipher: AllegedRc4,
+    inner: R,
+}
+
+impl AllegedRc4 {
+    /// Wraps `inner` so that everything read from it is decrypted first.
+    /// The keystream advances by exactly the number of bytes each read returns.
+    pub fn reader<R: Read>(self, inner: R) -> Rc4Reader<R> {
+        Rc4Reader {
+            cipher: self,
+            inner,
+        }
+    }
+}
+
+impl<R: Read> Rc4Reader<R> {
+    /// Returns the cipher and the inner reader.
+    pub fn into_parts(self) -> (AllegedRc4, R) {
+        (self.cipher, self.inner)
+    }
+}
+
+impl<R: Read> Read for Rc4Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.cipher.apply_keystream(&mut buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<W: Write> Rc4Writer<W> {
+    /// Returns the cipher and the inner writer.
+    pub fn into_parts(self) -> (AllegedRc4, W) {
+        (self.cipher, self.inner)
+    }
+}
+
+impl<W: Write> Write for Rc4Writer<W> {
+    /// Encrypts up to one chunk of `buf` and writes it. When the inner writer
+    /// takes only part of it, the keystream advances by just that much.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(CHUNK);
+        let mut chunk = [0u8; CHUNK];
+        chunk[..len].copy_from_slice(&buf[..len]);
+
+        let mut cipher = self.cipher.clone();
+        cipher.apply_keystream(&mut chunk[..len]);
+
+        let written = self.inner.write(&chunk[..len])?;
+        if written == len {
+            self.cipher = cipher;
+        } else {
+            self.cipher.fill_keystream(&mut chunk[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"stream adapter key";
+
+    /// Accepts at most `limit` bytes per call, like a congested socket.
+    struct Trickle {
+        data: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit);
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Returns at most `limit` bytes per call, like a slow socket.
+    struct Drip<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl Read for Drip<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn message() -> Vec<u8> {
+        (0..3000u32).map(|i| (i * 7 % 256) as u8).collect()
+    }
+
+    #[test]
+    fn test_writer_chunks() {
+        let message = message();
+        let mut expected = message.clone();
+        AllegedRc4::new(KEY).apply_keystream(&mut expected);
+
+        let mut writer = AllegedRc4::new(KEY).writer(Vec::new());
+        for chunk in message.chunks(13) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let (cipher, written) = writer.into_parts();
+        assert_eq!(written, expected);
+        assert_eq!(cipher.position(), message.len() as u64);
+    }
+
+    #[test]
+    fn test_writer_short_writes() {
+        let message = message();
+        let mut expected = message.clone();
+        AllegedRc4::new(KEY).apply_keystream(&mut expected);
+
+        let trickle = Trickle {
+            data: Vec::new(),
+            limit: 5,
+        };
+        let mut writer = AllegedRc4::new(KEY).writer(trickle);
+        writer.write_all(&message).unwrap();
+
+        let (cipher, trickle) = writer.into_parts();
+        assert_eq!(trickle.data, expected);
+        assert_eq!(cipher.position(), message.len() as u64);
+    }
+
+    #[test]
+    fn test_writer_into_reader() {
+        let message = message();
+
+        let mut writer = AllegedRc4::new(KEY).writer(Vec::new());
+        writer.write_all(&message).unwrap();
+        let (_, ciphertext) = writer.into_parts();
+
+        // Odd-sized reads over a source that itself returns short reads.
+        let drip = Drip {
+            data: &ciphertext,
+            limit: 11,
+        };
+        let mut reader = AllegedRc4::new(KEY).reader(drip);
+        let mut decrypted = Vec::new();
+        let mut buf = [0u8; 17];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            decrypted.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(decrypted, message);
+
+        let (cipher, _) = reader.into_parts();
+        assert_eq!(cipher.position(), message.len() as u64);
+
+        let mut decrypted = Vec::new();
+        AllegedRc4::new(KEY)
+            .reader(&ciphertext[..])
+            .read_to_end(&mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, message);
+    }
+}
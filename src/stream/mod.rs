@@ -1 +1,18 @@
 pub mod arc4;
+mod cascade;
+pub mod chacha20;
+#[cfg(feature = "std")]
+mod io;
+mod tee;
+
+pub use cascade::Cascade;
+#[cfg(feature = "std")]
+pub use io::{Rc4Reader, Rc4Writer};
+pub use tee::tee;
+
+/// A source of keystream that is XORed into data.
+/// Applying a freshly keyed copy a second time restores the input.
+pub trait Keystream {
+    /// XORs the next `buf.len()` keystream bytes into `buf`.
+    fn apply_keystream(&mut self, buf: &mut [u8]);
+}
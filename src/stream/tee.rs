@@ -0,0 +1,51 @@
+use super::Keystream;
+use alloc::vec::Vec;
+
+/// Encrypts `input` under two keystreams at once, e.g. the old and the new key
+/// during key rotation. Returns the output of `primary` and of `secondary`.
+///
+/// Both keystreams are cloned first, so each output starts from the state the
+/// stream had when passed in and the originals are left untouched.
+pub fn tee<A, B>(primary: &A, secondary: &B, input: &[u8]) -> (Vec<u8>, Vec<u8>)
+where
+    A: Keystream + Clone,
+    B: Keystream + Clone,
+{
+    let mut first = input.to_vec();
+    primary.clone().apply_keystream(&mut first);
+
+    let mut second = input.to_vec();
+    secondary.clone().apply_keystream(&mut second);
+
+    (first, second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::des::Des;
+    use crate::modes::Ctr;
+    use crate::stream::arc4::AllegedRc4;
+
+    #[test]
+    fn test_tee() {
+        let message = b"re-encrypt me under a fresh key";
+        let rc4 = AllegedRc4::new(b"old key");
+        let ctr = Ctr::new(Des::new(0x1334_5779_9BBC_DFF1), &[0u8; 8]).unwrap();
+
+        let (old, new) = tee(&rc4, &ctr, message);
+        assert_ne!(old, new);
+
+        let mut buf = old;
+        AllegedRc4::new(b"old key").apply_keystream(&mut buf);
+        assert_eq!(&buf[..], message);
+
+        let mut buf = new;
+        ctr.clone().apply_keystream(&mut buf);
+        assert_eq!(&buf[..], message);
+
+        // The originals were not advanced.
+        assert_eq!(rc4.position(), 0);
+        assert_eq!(ctr.counter(), 0);
+    }
+}
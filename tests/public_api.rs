@@ -0,0 +1,102 @@
+use encrust::block::BlockCipher;
+use encrust::block::aes::Aes;
+use encrust::block::des::Des;
+use encrust::mac::Cmac;
+use encrust::modes::{Cbc, Ctr};
+use encrust::padding::{pkcs7_pad, pkcs7_unpad};
+use encrust::stream::arc4::AllegedRc4;
+use encrust::{Error, Scheme, open, seal};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+const DES_KEY: u64 = 0x1334_5779_9BBC_DFF1;
+const AES_KEY: [u32; 4] = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+const MESSAGE: &[u8] = b"Attack at dawn, then retreat at dusk.";
+
+#[test]
+fn des_block() {
+    let cipher = Des::new(DES_KEY);
+    let ciphertext = cipher.encrypt(0x0123_4567_89AB_CDEF);
+
+    assert_eq!(ciphertext, 0x85E8_1354_0F0A_B405);
+    assert_eq!(cipher.decrypt(ciphertext), 0x0123_4567_89AB_CDEF);
+}
+
+#[test]
+fn aes_block() {
+    let cipher = Aes::new(&AES_KEY).unwrap();
+    let plaintext = *b"sixteen byte msg";
+
+    assert_eq!(cipher.decrypt(cipher.encrypt(plaintext)), plaintext);
+}
+
+#[test]
+fn block_cipher_trait() {
+    let mut block = 0x0123_4567_89AB_CDEFu64.to_be_bytes();
+    Des::new(DES_KEY).encrypt_block(&mut block);
+    assert_eq!(block, 0x85E8_1354_0F0A_B405u64.to_be_bytes());
+}
+
+#[test]
+fn arc4_stream() {
+    let mut buf = MESSAGE.to_vec();
+    AllegedRc4::new(b"Secret").apply_keystream(&mut buf);
+    assert_ne!(buf, MESSAGE);
+
+    AllegedRc4::new(b"Secret").apply_keystream(&mut buf);
+    assert_eq!(buf, MESSAGE);
+}
+
+#[test]
+fn cbc_mode() {
+    let iv = [0x42u8; 16];
+    let ciphertext = Cbc::new(Aes::new(&AES_KEY).unwrap(), &iv)
+        .unwrap()
+        .encrypt_padded(MESSAGE);
+
+    let plaintext = Cbc::new(Aes::new(&AES_KEY).unwrap(), &iv)
+        .unwrap()
+        .decrypt_padded(&ciphertext)
+        .unwrap();
+    assert_eq!(plaintext, MESSAGE);
+}
+
+#[test]
+fn ctr_mode() {
+    let des = Des::new(DES_KEY);
+    let mut buf = MESSAGE.to_vec();
+
+    Ctr::new(&des, &[0u8; 8]).unwrap().apply_keystream(&mut buf);
+    Ctr::new(&des, &[0u8; 8]).unwrap().apply_keystream(&mut buf);
+    assert_eq!(buf, MESSAGE);
+}
+
+#[test]
+fn padding() {
+    let mut buf = MESSAGE.to_vec();
+    pkcs7_pad(&mut buf, 8);
+    assert_eq!(buf.len(), 40);
+
+    pkcs7_unpad(&mut buf, 8).unwrap();
+    assert_eq!(buf, MESSAGE);
+}
+
+#[test]
+fn cmac() {
+    let mut mac = Cmac::new(Aes::new(&AES_KEY).unwrap());
+    mac.update(MESSAGE);
+    let tag = mac.finalize();
+
+    let mut mac = Cmac::new(Aes::new(&AES_KEY).unwrap());
+    mac.update(b"Attack at dusk");
+    assert_eq!(mac.verify(&tag), Err(Error::AuthenticationFailed));
+}
+
+#[test]
+fn sealed_message() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let des = Des::new(DES_KEY);
+
+    let sealed = seal(&des, Scheme::CbcPkcs7, &mut rng, MESSAGE);
+    assert_eq!(open(&des, &sealed).unwrap(), MESSAGE);
+}
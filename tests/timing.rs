@@ -0,0 +1,96 @@
+//! Heuristic timing checks, ignored by default. Run with
+//! `cargo test --release --test timing -- --ignored`.
+//!
+//! Each check times block encryption for two classes of input (all-zero blocks
+//! and random blocks), interleaving the classes in batches so that drift in CPU
+//! frequency hits both alike, and compares the medians. A large gap hints at a
+//! data-dependent branch. A pass proves nothing: cache-timing leaks from table
+//! lookups are far too small to see this way, and results depend on the machine.
+//!
+//! A new cipher opts in with a one-line test calling [`assert_similar_timing`].
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use encrust::block::BlockCipher;
+use encrust::block::TripleDes;
+use encrust::block::aes::Aes;
+use encrust::block::des::Des;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+/// Batches per input class; the median batch time is compared.
+const BATCHES: usize = 101;
+/// Blocks encrypted per batch.
+const BATCH_SIZE: usize = 2000;
+/// Largest allowed relative gap between the two medians.
+const THRESHOLD: f64 = 0.15;
+
+/// Times one batch of encryptions over `blocks`.
+fn time_batch<C: BlockCipher>(cipher: &C, blocks: &mut [u8]) -> Duration {
+    let start = Instant::now();
+    for block in blocks.chunks_exact_mut(C::BLOCK_SIZE) {
+        cipher.encrypt_block(black_box(block));
+    }
+    black_box(blocks);
+    start.elapsed()
+}
+
+fn median(times: &mut [Duration]) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+/// Compares the encryption time of all-zero blocks against random blocks and
+/// panics when the medians differ by more than [`THRESHOLD`].
+pub fn assert_similar_timing<C: BlockCipher>(name: &str, cipher: &C) {
+    let mut rng = StdRng::seed_from_u64(508);
+    let len = C::BLOCK_SIZE * BATCH_SIZE;
+
+    let mut zero_times = Vec::with_capacity(BATCHES);
+    let mut random_times = Vec::with_capacity(BATCHES);
+    for _ in 0..BATCHES {
+        let mut zeros = vec![0u8; len];
+        let mut random = vec![0u8; len];
+        rng.fill_bytes(&mut random);
+
+        zero_times.push(time_batch(cipher, &mut zeros));
+        random_times.push(time_batch(cipher, &mut random));
+    }
+
+    let zero = median(&mut zero_times).as_secs_f64();
+    let random = median(&mut random_times).as_secs_f64();
+    let gap = (zero - random).abs() / zero.min(random);
+    assert!(
+        gap <= THRESHOLD,
+        "{}: zero blocks {:.3?} vs random blocks {:.3?} per batch ({:.1}% apart)",
+        name,
+        Duration::from_secs_f64(zero),
+        Duration::from_secs_f64(random),
+        gap * 100.0
+    );
+}
+
+#[test]
+#[ignore]
+fn des_timing() {
+    assert_similar_timing("DES", &Des::new(0x1334_5779_9BBC_DFF1));
+}
+
+#[test]
+#[ignore]
+fn triple_des_timing() {
+    let cipher = TripleDes::new(
+        0x0123_4567_89AB_CDEF,
+        0x2345_6789_ABCD_EF01,
+        0x4567_89AB_CDEF_0123,
+    );
+    assert_similar_timing("3DES", &cipher);
+}
+
+#[test]
+#[ignore]
+fn aes_timing() {
+    let key = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+    assert_similar_timing("AES-128", &Aes::new(&key).unwrap());
+}